@@ -0,0 +1,305 @@
+use crate::hash::FastMap;
+use std::hash::Hash;
+
+/// Shape of an eventually periodic sequence: states from index `prefix_len` on repeat every
+/// `cycle_len` steps.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub struct Cycle {
+    pub prefix_len: usize,
+    pub cycle_len: usize,
+}
+
+#[allow(dead_code)]
+impl Cycle {
+    /// Smallest index holding the same state as index `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix_len {
+            n
+        } else {
+            self.prefix_len + (n - self.prefix_len) % self.cycle_len
+        }
+    }
+}
+
+/// Floyd's tortoise and hare over `start, next(start), next(next(start)), ...`, only keeps two
+/// states in memory but calls `next` about three times as often as [`brent`].
+#[allow(dead_code)]
+pub fn floyd<S: Eq>(start: S, mut next: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = next(&start);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        hare = next(&hare);
+    }
+
+    let mut prefix_len = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        prefix_len += 1;
+    }
+
+    let mut cycle_len = 1;
+    hare = next(&tortoise);
+    while tortoise != hare {
+        hare = next(&hare);
+        cycle_len += 1;
+    }
+    Cycle {
+        prefix_len,
+        cycle_len,
+    }
+}
+
+/// Brent's power of two search, finds the cycle length first then the prefix.
+#[allow(dead_code)]
+pub fn brent<S: Eq>(start: S, mut next: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut cycle_len = 1;
+    let mut tortoise = next(&start);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        if power == cycle_len {
+            tortoise = hare;
+            hare = next(&tortoise);
+            power *= 2;
+            cycle_len = 1;
+        } else {
+            hare = next(&hare);
+            cycle_len += 1;
+        }
+    }
+
+    let mut tortoise = start;
+    let mut hare = next(&tortoise);
+    for _ in 1..cycle_len {
+        hare = next(&hare);
+    }
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        prefix_len += 1;
+    }
+    Cycle {
+        prefix_len,
+        cycle_len,
+    }
+}
+
+/// [`floyd`] over a cloneable iterator, `None` when it ends before repeating.
+#[allow(dead_code)]
+pub fn floyd_iter<I>(iter: I) -> Option<Cycle>
+where
+    I: Iterator + Clone,
+    I::Item: Eq,
+{
+    let mut tortoise = iter.clone();
+    let mut hare = iter.clone();
+    let (mut tortoise_item, mut hare_item) = (tortoise.nth(1)?, hare.nth(2)?);
+    while tortoise_item != hare_item {
+        tortoise_item = tortoise.next()?;
+        hare_item = hare.nth(1)?;
+    }
+
+    let mut prefix_len = 0;
+    let mut tortoise = iter;
+    let mut tortoise_item = tortoise.next()?;
+    while tortoise_item != hare_item {
+        tortoise_item = tortoise.next()?;
+        hare_item = hare.next()?;
+        prefix_len += 1;
+    }
+
+    let mut cycle_len = 1;
+    let mut hare = tortoise;
+    while hare.next()? != tortoise_item {
+        cycle_len += 1;
+    }
+    Some(Cycle {
+        prefix_len,
+        cycle_len,
+    })
+}
+
+/// [`brent`] over a cloneable iterator, `None` when it ends before repeating.
+#[allow(dead_code)]
+pub fn brent_iter<I>(iter: I) -> Option<Cycle>
+where
+    I: Iterator + Clone,
+    I::Item: Eq,
+{
+    let mut power = 1;
+    let mut cycle_len = 1;
+    let mut hare = iter.clone();
+    let mut tortoise_item = hare.next()?;
+    let mut hare_item = hare.next()?;
+    while tortoise_item != hare_item {
+        if power == cycle_len {
+            tortoise_item = hare_item;
+            power *= 2;
+            cycle_len = 0;
+        }
+        hare_item = hare.next()?;
+        cycle_len += 1;
+    }
+
+    let mut tortoise = iter.clone();
+    let mut hare = iter;
+    let mut hare_item = hare.nth(cycle_len)?;
+    let mut tortoise_item = tortoise.next()?;
+    let mut prefix_len = 0;
+    while tortoise_item != hare_item {
+        tortoise_item = tortoise.next()?;
+        hare_item = hare.next()?;
+        prefix_len += 1;
+    }
+    Some(Cycle {
+        prefix_len,
+        cycle_len,
+    })
+}
+
+/// Remembers the index of every state seen, returning the cycle with the states up to the
+/// first repeat, which is `prefix_len + cycle_len` states long.
+#[allow(dead_code)]
+pub fn find_cycle_hashed<S>(start: S, mut next: impl FnMut(&S) -> S) -> (Cycle, Vec<S>)
+where
+    S: Eq + Hash + Clone,
+{
+    let mut seen = FastMap::default();
+    let mut states = Vec::new();
+    let mut state = start;
+    loop {
+        if let Some(&prefix_len) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix_len,
+                cycle_len: states.len() - prefix_len,
+            };
+            return (cycle, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let following = next(&state);
+        states.push(state);
+        state = following;
+    }
+}
+
+/// [`find_cycle_hashed`] over an iterator, `None` when it ends before repeating.
+#[allow(dead_code)]
+pub fn find_cycle_hashed_iter<I>(iter: I) -> Option<(Cycle, Vec<I::Item>)>
+where
+    I: Iterator,
+    I::Item: Eq + Hash + Clone,
+{
+    let mut seen = FastMap::default();
+    let mut states = Vec::new();
+    for state in iter {
+        if let Some(&prefix_len) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix_len,
+                cycle_len: states.len() - prefix_len,
+            };
+            return Some((cycle, states));
+        }
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+    }
+    None
+}
+
+/// State after applying `next` `n` times, skipping over the full cycles once the sequence
+/// repeats.
+#[allow(dead_code)]
+pub fn nth_state<S>(start: S, mut next: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Eq + Hash + Clone,
+{
+    let mut seen = FastMap::default();
+    let mut states = Vec::new();
+    let mut state = start;
+    while states.len() < n {
+        if let Some(&prefix_len) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix_len,
+                cycle_len: states.len() - prefix_len,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(state.clone(), states.len());
+        let following = next(&state);
+        states.push(state);
+        state = following;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cycle::{
+        brent, brent_iter, find_cycle_hashed, find_cycle_hashed_iter, floyd, floyd_iter, nth_state,
+        Cycle,
+    };
+
+    // 0 -> 1 -> ... -> prefix_len -> ... -> prefix_len + cycle_len - 1 -> prefix_len
+    fn rho(prefix_len: usize, cycle_len: usize) -> impl Fn(&usize) -> usize + Clone {
+        move |state| {
+            if state + 1 == prefix_len + cycle_len {
+                prefix_len
+            } else {
+                state + 1
+            }
+        }
+    }
+
+    #[test]
+    pub fn cycle_finders_agree() {
+        for prefix_len in 0..8 {
+            for cycle_len in 1..8 {
+                let expected = Cycle {
+                    prefix_len,
+                    cycle_len,
+                };
+                let next = rho(prefix_len, cycle_len);
+                let iter = std::iter::successors(Some(0), {
+                    let next = next.clone();
+                    move |state| Some(next(state))
+                });
+                assert_eq!(floyd(0, &next), expected);
+                assert_eq!(brent(0, &next), expected);
+                assert_eq!(floyd_iter(iter.clone()), Some(expected));
+                assert_eq!(brent_iter(iter.clone()), Some(expected));
+                let (cycle, states) = find_cycle_hashed(0, &next);
+                assert_eq!(cycle, expected);
+                assert_eq!(states, (0..prefix_len + cycle_len).collect::<Vec<_>>());
+                assert_eq!(find_cycle_hashed_iter(iter).map(|(c, _)| c), Some(expected));
+            }
+        }
+        assert_eq!(floyd_iter(0..10), None);
+        assert_eq!(brent_iter(0..10), None);
+        assert_eq!(find_cycle_hashed_iter(0..10), None);
+    }
+
+    #[test]
+    pub fn nth() {
+        let next = rho(3, 4);
+        for n in 0..30 {
+            let expected = (0..n).fold(0, |state, _| next(&state));
+            assert_eq!(nth_state(0, &next, n), expected);
+        }
+        assert_eq!(
+            nth_state(0, next, 1_000_000_000),
+            3 + (1_000_000_000 - 3) % 4
+        );
+        assert_eq!(
+            Cycle {
+                prefix_len: 3,
+                cycle_len: 4
+            }
+            .reduce(10),
+            6
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error::Error;
+use crate::space::index::IndexMapping;
+use std::marker::PhantomData;
+
+#[derive(Clone, Copy, Debug)]
+struct UnionEntry {
+    child: usize,
+    root: usize,
+    rank_increased: bool,
+}
+
+/// Union-find over the elements `0..len()` by rank with path compression. When created with
+/// [`DisjointSet::with_rollback`] every union is logged so it can be undone, path compression
+/// is then disabled to keep the log valid.
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+    log: Option<Vec<UnionEntry>>,
+}
+
+#[allow(dead_code)]
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            count: len,
+            log: None,
+        }
+    }
+
+    pub fn with_rollback(len: usize) -> Self {
+        Self {
+            log: Some(Vec::new()),
+            ..Self::new(len)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Representative of the set holding `elem`, panics when `elem` is out of bound.
+    pub fn find(&mut self, elem: usize) -> usize {
+        let mut root = elem;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        if self.log.is_none() {
+            let mut elem = elem;
+            while self.parent[elem] != root {
+                elem = std::mem::replace(&mut self.parent[elem], root);
+            }
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`, returns `false` when they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut root, mut child) = (self.find(a), self.find(b));
+        if root == child {
+            return false;
+        }
+        if self.rank[root] < self.rank[child] {
+            std::mem::swap(&mut root, &mut child);
+        }
+        let rank_increased = self.rank[root] == self.rank[child];
+        if rank_increased {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.count -= 1;
+        if let Some(log) = self.log.as_mut() {
+            log.push(UnionEntry {
+                child,
+                root,
+                rank_increased,
+            });
+        }
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set holding `elem`.
+    pub fn size(&mut self, elem: usize) -> usize {
+        let root = self.find(elem);
+        self.size[root]
+    }
+
+    /// Point to [`DisjointSet::rollback`] to, `None` without a rollback log.
+    pub fn snapshot(&self) -> Option<usize> {
+        self.log.as_ref().map(Vec::len)
+    }
+
+    /// Undoes every union made since `snapshot` was taken.
+    pub fn rollback(&mut self, snapshot: usize) -> Result<(), Error> {
+        let log = self
+            .log
+            .as_mut()
+            .ok_or_else(|| Error::InvalidState("disjoint set has no rollback log".into()))?;
+        if snapshot > log.len() {
+            return Err(Error::InvalidState(
+                "snapshot is newer than the rollback log".into(),
+            ));
+        }
+        for entry in log.drain(snapshot..).rev() {
+            self.parent[entry.child] = entry.child;
+            self.size[entry.root] -= self.size[entry.child];
+            if entry.rank_increased {
+                self.rank[entry.root] -= 1;
+            }
+            self.count += 1;
+        }
+        Ok(())
+    }
+}
+
+/// [`DisjointSet`] keyed through an [`IndexMapping`], e.g.
+/// [`GridIndex`](crate::space::index::GridIndex) for grid positions.
+#[derive(Clone, Debug)]
+pub struct MappedDisjointSet<K, M: IndexMapping<K>> {
+    inner: DisjointSet,
+    mapping: M,
+    phantom_data: PhantomData<K>,
+}
+
+#[allow(dead_code)]
+impl<K, M: IndexMapping<K>> MappedDisjointSet<K, M> {
+    pub fn new(mapping: M) -> Self {
+        Self {
+            inner: DisjointSet::new(mapping.len()),
+            mapping,
+            phantom_data: PhantomData,
+        }
+    }
+
+    pub fn with_rollback(mapping: M) -> Self {
+        Self {
+            inner: DisjointSet::with_rollback(mapping.len()),
+            mapping,
+            phantom_data: PhantomData,
+        }
+    }
+
+    pub fn mapping(&self) -> &M {
+        &self.mapping
+    }
+
+    fn index_of(&self, key: &K) -> Result<usize, Error> {
+        self.mapping
+            .index_of(key)
+            .ok_or_else(|| Error::InvalidState("out of bound".into()))
+    }
+
+    pub fn find(&mut self, key: &K) -> Result<K, Error> {
+        let index = self.index_of(key)?;
+        let root = self.inner.find(index);
+        self.mapping
+            .key_of(root)
+            .ok_or_else(|| Error::InvalidState("out of bound".into()))
+    }
+
+    pub fn union(&mut self, a: &K, b: &K) -> Result<bool, Error> {
+        let (a, b) = (self.index_of(a)?, self.index_of(b)?);
+        Ok(self.inner.union(a, b))
+    }
+
+    pub fn same(&mut self, a: &K, b: &K) -> Result<bool, Error> {
+        let (a, b) = (self.index_of(a)?, self.index_of(b)?);
+        Ok(self.inner.same(a, b))
+    }
+
+    pub fn size(&mut self, key: &K) -> Result<usize, Error> {
+        let index = self.index_of(key)?;
+        Ok(self.inner.size(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// See [`DisjointSet::snapshot`].
+    pub fn snapshot(&self) -> Option<usize> {
+        self.inner.snapshot()
+    }
+
+    /// See [`DisjointSet::rollback`].
+    pub fn rollback(&mut self, snapshot: usize) -> Result<(), Error> {
+        self.inner.rollback(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::disjoint_set::{DisjointSet, MappedDisjointSet};
+    use crate::error::Error;
+    use crate::space::index::GridIndex;
+
+    fn naive_labels(len: usize, unions: &[(usize, usize)]) -> Vec<usize> {
+        let mut labels: Vec<_> = (0..len).collect();
+        for &(a, b) in unions {
+            let (from, to) = (labels[a], labels[b]);
+            labels
+                .iter_mut()
+                .filter(|l| **l == from)
+                .for_each(|l| *l = to);
+        }
+        labels
+    }
+
+    fn assert_same_partition(set: &mut DisjointSet, labels: &[usize]) {
+        for a in 0..labels.len() {
+            for b in 0..labels.len() {
+                assert_eq!(set.same(a, b), labels[a] == labels[b]);
+            }
+            let size = labels.iter().filter(|l| **l == labels[a]).count();
+            assert_eq!(set.size(a), size);
+        }
+        let mut distinct = labels.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(set.count(), distinct.len());
+    }
+
+    #[test]
+    pub fn matches_naive() -> Result<(), Error> {
+        let mut seed = 3u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % 20
+        };
+        let unions: Vec<_> = (0..30).map(|_| (next(), next())).collect();
+
+        let mut set = DisjointSet::new(20);
+        unions.iter().for_each(|&(a, b)| {
+            set.union(a, b);
+        });
+        assert_same_partition(&mut set, &naive_labels(20, &unions));
+
+        let mut set = DisjointSet::with_rollback(20);
+        let mut snapshots = Vec::new();
+        for &(a, b) in unions.iter() {
+            snapshots.push(set.snapshot().unwrap());
+            set.union(a, b);
+        }
+        for (done, snapshot) in snapshots.into_iter().enumerate().rev() {
+            set.rollback(snapshot)?;
+            assert_same_partition(&mut set, &naive_labels(20, &unions[..done]));
+        }
+        assert!(DisjointSet::new(1).rollback(0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn grid_keys() -> Result<(), Error> {
+        let mut set = MappedDisjointSet::new(GridIndex::from([3, 3]));
+        assert!(set.union(&[0, 0], &[1, 0])?);
+        assert!(set.union(&[1, 0], &[1, 1])?);
+        assert!(!set.union(&[0, 0], &[1, 1])?);
+        assert!(set.same(&[0, 0], &[1, 1])?);
+        assert!(!set.same(&[0, 0], &[2, 2])?);
+        assert_eq!(set.size(&[1, 1])?, 3);
+        assert_eq!(set.find(&[1, 1])?, set.find(&[0, 0])?);
+        assert_eq!(set.count(), 7);
+        assert_eq!(set.len(), 9);
+        assert!(set.union(&[3, 0], &[0, 0]).is_err());
+
+        let mut set = MappedDisjointSet::with_rollback(GridIndex::from([2, 2]));
+        let snapshot = set.snapshot().unwrap();
+        set.union(&[0, 0], &[1, 1])?;
+        assert_eq!(set.count(), 3);
+        set.rollback(snapshot)?;
+        assert!(!set.same(&[0, 0], &[1, 1])?);
+        assert_eq!(set.count(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,505 @@
+use crate::error::Error;
+use crate::hash::FastHashState;
+use bit_set::BitSet;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::iter;
+
+/// Index of an interned node, in insertion order.
+pub type NodeId = usize;
+
+/// Adjacency-list graph over interned nodes `N` with edge weight `E`. An undirected graph stores
+/// every edge in both directions.
+#[derive(Clone, Debug)]
+pub struct Graph<N, E> {
+    directed: bool,
+    nodes: IndexSet<N, FastHashState>,
+    out_edges: Vec<Vec<(NodeId, E)>>,
+    in_edges: Vec<Vec<NodeId>>,
+}
+
+#[allow(dead_code)]
+impl<N: Eq + Hash, E> Graph<N, E> {
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            nodes: IndexSet::default(),
+            out_edges: Vec::new(),
+            in_edges: Vec::new(),
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Id of `node`, adding it when it is not in the graph yet.
+    pub fn intern(&mut self, node: N) -> NodeId {
+        let (id, inserted) = self.nodes.insert_full(node);
+        if inserted {
+            self.out_edges.push(Vec::new());
+            self.in_edges.push(Vec::new());
+        }
+        id
+    }
+
+    pub fn node_id(&self, node: &N) -> Option<NodeId> {
+        self.nodes.get_index_of(node)
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&N> {
+        self.nodes.get_index(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len()
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: E) -> (NodeId, NodeId)
+    where
+        E: Clone,
+    {
+        let from = self.intern(from);
+        let to = self.intern(to);
+        self.add_edge_by_id(from, to, weight);
+        (from, to)
+    }
+
+    pub fn add_edge_by_id(&mut self, from: NodeId, to: NodeId, weight: E)
+    where
+        E: Clone,
+    {
+        if !self.directed && from != to {
+            self.out_edges[to].push((from, weight.clone()));
+            self.in_edges[from].push(to);
+        }
+        self.out_edges[from].push((to, weight));
+        self.in_edges[to].push(from);
+    }
+
+    /// Same as [`Graph::add_edge`] but keeps the graph simple, skipping the edge when `from`
+    /// already links to `to`. Returns whether the edge was added.
+    pub fn add_edge_unique(&mut self, from: N, to: N, weight: E) -> bool
+    where
+        E: Clone,
+    {
+        let from = self.intern(from);
+        let to = self.intern(to);
+        self.add_edge_unique_by_id(from, to, weight)
+    }
+
+    pub fn add_edge_unique_by_id(&mut self, from: NodeId, to: NodeId, weight: E) -> bool
+    where
+        E: Clone,
+    {
+        if self.has_edge(from, to) {
+            return false;
+        }
+        self.add_edge_by_id(from, to, weight);
+        true
+    }
+
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, E)] {
+        self.out_edges
+            .get(id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges(id).iter().map(|(to, _)| *to)
+    }
+
+    pub fn predecessors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.in_edges
+            .get(id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+    }
+
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.neighbors(from).any(|id| id == to)
+    }
+
+    pub fn out_degree(&self, id: NodeId) -> usize {
+        self.edges(id).len()
+    }
+
+    pub fn in_degree(&self, id: NodeId) -> usize {
+        self.in_edges.get(id).map_or(0, Vec::len)
+    }
+
+    /// Number of edges touching `id`, a self loop counts twice in an undirected graph.
+    pub fn degree(&self, id: NodeId) -> usize {
+        if self.directed {
+            self.out_degree(id) + self.in_degree(id)
+        } else {
+            let self_loops = self.neighbors(id).filter(|&to| to == id).count();
+            self.out_degree(id) + self_loops
+        }
+    }
+
+    /// Nodes linked to `id` in either direction, each once.
+    fn undirected_neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut seen = BitSet::new();
+        self.neighbors(id)
+            .chain(self.predecessors(id))
+            .filter(move |next| seen.insert(*next))
+    }
+
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, Error>
+    where
+        N: Debug,
+    {
+        self.topological_sort_within(&self.node_ids().collect())
+    }
+
+    /// Topological order of the subgraph induced by `nodes`, ties broken by insertion order. Fails
+    /// with the nodes of one cycle when there is no such order, or when the graph is undirected.
+    pub fn topological_sort_within(&self, nodes: &BitSet) -> Result<Vec<NodeId>, Error>
+    where
+        N: Debug,
+    {
+        if !self.directed {
+            return Err(Error::InvalidState(
+                "topological order of an undirected graph".into(),
+            ));
+        }
+        let mut in_degree = vec![0; self.len()];
+        for from in nodes.iter() {
+            self.neighbors(from)
+                .filter(|to| nodes.contains(*to))
+                .for_each(|to| in_degree[to] += 1);
+        }
+
+        let mut work: VecDeque<_> = nodes.iter().filter(|id| in_degree[*id] == 0).collect();
+        let mut res = Vec::with_capacity(nodes.len());
+        while let Some(from) = work.pop_front() {
+            res.push(from);
+            for to in self.neighbors(from).filter(|to| nodes.contains(*to)) {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    work.push_back(to);
+                }
+            }
+        }
+
+        if res.len() == nodes.len() {
+            return Ok(res);
+        }
+
+        // Every node left has a predecessor that is also left, walking back must loop.
+        let mut walk = vec![nodes.iter().find(|id| in_degree[*id] > 0).unwrap()];
+        let mut seen = BitSet::new();
+        while let Some(&id) = walk.last() {
+            if !seen.insert(id) {
+                break;
+            }
+            let prev = self
+                .predecessors(id)
+                .find(|prev| nodes.contains(*prev) && in_degree[*prev] > 0)
+                .unwrap();
+            walk.push(prev);
+        }
+        let repeated = walk.pop().unwrap();
+        let start = walk.iter().position(|id| *id == repeated).unwrap();
+        let cycle = iter::once(&repeated)
+            .chain(walk[start..].iter().rev())
+            .map(|id| format!("{:?}", self.nodes[*id]))
+            .collect::<Vec<_>>();
+        Err(Error::InvalidState(
+            format!("cycle detected: {}", cycle.join(" -> ")).into(),
+        ))
+    }
+
+    /// Strongly connected components in reverse topological order of the condensed graph.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut index = vec![None; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = BitSet::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut counter = 0;
+        let mut res = Vec::new();
+
+        for root in self.node_ids() {
+            if index[root].is_some() {
+                continue;
+            }
+            let mut call = vec![(root, 0)];
+            index[root] = Some(counter);
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack.insert(root);
+
+            while let Some(&(v, edge)) = call.last() {
+                if let Some(&(w, _)) = self.edges(v).get(edge) {
+                    call.last_mut().unwrap().1 += 1;
+                    match index[w] {
+                        None => {
+                            index[w] = Some(counter);
+                            low[w] = counter;
+                            counter += 1;
+                            stack.push(w);
+                            on_stack.insert(w);
+                            call.push((w, 0));
+                        }
+                        Some(index_w) if on_stack.contains(w) => low[v] = low[v].min(index_w),
+                        _ => {}
+                    }
+                } else {
+                    call.pop();
+                    if let Some(&(parent, _)) = call.last() {
+                        low[parent] = low[parent].min(low[v]);
+                    }
+                    if Some(low[v]) == index[v] {
+                        let mut component = Vec::new();
+                        while let Some(w) = stack.pop() {
+                            on_stack.remove(w);
+                            component.push(w);
+                            if w == v {
+                                break;
+                            }
+                        }
+                        res.push(component);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Connected components ignoring edge direction.
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut visited = BitSet::with_capacity(self.len());
+        let mut res = Vec::new();
+        for root in self.node_ids() {
+            if !visited.insert(root) {
+                continue;
+            }
+            let mut component = vec![root];
+            let mut idx = 0;
+            while let Some(&id) = component.get(idx) {
+                idx += 1;
+                for next in self.undirected_neighbors(id) {
+                    if visited.insert(next) {
+                        component.push(next);
+                    }
+                }
+            }
+            res.push(component);
+        }
+        res
+    }
+
+    /// Two-coloring ignoring edge direction, `None` when the graph has an odd cycle.
+    pub fn bipartition(&self) -> Option<Vec<bool>> {
+        let mut color = vec![None; self.len()];
+        for root in self.node_ids() {
+            if color[root].is_some() {
+                continue;
+            }
+            color[root] = Some(false);
+            let mut work = VecDeque::from([root]);
+            while let Some(id) = work.pop_front() {
+                let side = color[id]?;
+                for next in self.undirected_neighbors(id) {
+                    match color[next] {
+                        None => {
+                            color[next] = Some(!side);
+                            work.push_back(next);
+                        }
+                        Some(next_side) if next_side == side => return None,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        color.into_iter().collect()
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.bipartition().is_some()
+    }
+
+    /// Every maximal clique ignoring edge direction, by Bron–Kerbosch with pivoting.
+    pub fn maximal_cliques(&self) -> Vec<Vec<NodeId>> {
+        let adjacency = self
+            .node_ids()
+            .map(|id| {
+                self.undirected_neighbors(id)
+                    .filter(|next| *next != id)
+                    .collect::<BitSet>()
+            })
+            .collect::<Vec<_>>();
+        let mut res = Vec::new();
+        bron_kerbosch(
+            &adjacency,
+            &mut Vec::new(),
+            self.node_ids().collect(),
+            BitSet::new(),
+            &mut res,
+        );
+        res
+    }
+
+    pub fn maximum_clique(&self) -> Vec<NodeId> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by_key(Vec::len)
+            .unwrap_or_default()
+    }
+}
+
+fn bron_kerbosch(
+    adjacency: &[BitSet],
+    clique: &mut Vec<NodeId>,
+    mut potentials: BitSet,
+    mut excluded: BitSet,
+    cliques: &mut Vec<Vec<NodeId>>,
+) {
+    let Some(pivot) = potentials
+        .union(&excluded)
+        .max_by_key(|id| adjacency[*id].intersection(&potentials).count())
+    else {
+        cliques.push(clique.clone());
+        return;
+    };
+    let candidates = potentials.difference(&adjacency[pivot]).collect::<Vec<_>>();
+    for v in candidates {
+        clique.push(v);
+        bron_kerbosch(
+            adjacency,
+            clique,
+            potentials.intersection(&adjacency[v]).collect(),
+            excluded.intersection(&adjacency[v]).collect(),
+            cliques,
+        );
+        clique.pop();
+        potentials.remove(v);
+        excluded.insert(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::adjacency::Graph;
+
+    fn names<'a>(graph: &Graph<&'a str, ()>, ids: &[usize]) -> Vec<&'a str> {
+        let mut res = ids
+            .iter()
+            .map(|id| *graph.node(*id).unwrap())
+            .collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    pub fn topological_sort() {
+        let mut graph = Graph::directed();
+        for (from, to) in [("a", "b"), ("a", "c"), ("c", "b"), ("b", "d"), ("e", "d")] {
+            graph.add_edge(from, to, ());
+        }
+        let order = graph.topological_sort().unwrap();
+        assert_eq!(
+            order
+                .iter()
+                .map(|id| *graph.node(*id).unwrap())
+                .collect::<Vec<_>>(),
+            ["a", "e", "c", "b", "d"]
+        );
+        assert_eq!(graph.in_degree(graph.node_id(&"d").unwrap()), 2);
+        assert_eq!(graph.degree(graph.node_id(&"b").unwrap()), 3);
+
+        graph.add_edge("d", "c", ());
+        let err = graph.topological_sort().unwrap_err().to_string();
+        assert!(err.contains(r#""b" -> "d" -> "c" -> "b""#), "{}", err);
+
+        let within = ["a", "e"]
+            .iter()
+            .map(|node| graph.node_id(node).unwrap())
+            .collect();
+        assert_eq!(graph.topological_sort_within(&within).unwrap().len(), 2);
+
+        let mut undirected = Graph::undirected();
+        undirected.add_edge("a", "b", ());
+        assert!(undirected.topological_sort().is_err());
+    }
+
+    #[test]
+    pub fn components() {
+        let mut graph = Graph::directed();
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "f")] {
+            graph.add_edge(from, to, ());
+        }
+        let mut scc = graph
+            .strongly_connected_components()
+            .iter()
+            .map(|component| names(&graph, component))
+            .collect::<Vec<_>>();
+        scc.sort();
+        assert_eq!(scc, [vec!["a", "b", "c"], vec!["d"], vec!["e"], vec!["f"]]);
+        let cc = graph.connected_components();
+        assert_eq!(cc.len(), 2);
+        assert_eq!(names(&graph, &cc[0]), ["a", "b", "c", "d"]);
+        assert!(!graph.is_bipartite());
+
+        graph.add_edge("b", "a", ());
+        let a = graph.node_id(&"a").unwrap();
+        let mut next = graph.undirected_neighbors(a).collect::<Vec<_>>();
+        next.sort();
+        assert_eq!(names(&graph, &next), ["b", "c"]);
+    }
+
+    #[test]
+    pub fn cliques_and_bipartite() {
+        let mut graph = Graph::undirected();
+        for (from, to) in [
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("a", "d"),
+            ("d", "e"),
+        ] {
+            graph.add_edge(from, to, ());
+        }
+        assert_eq!(graph.maximal_cliques().len(), 2);
+        assert_eq!(names(&graph, &graph.maximum_clique()), ["a", "b", "c", "d"]);
+        assert_eq!(graph.degree(graph.node_id(&"d").unwrap()), 4);
+        assert!(!graph.add_edge_unique("d", "a", ()));
+        assert!(!graph.add_edge_unique("a", "b", ()));
+        assert_eq!(graph.degree(graph.node_id(&"a").unwrap()), 3);
+        assert!(graph.add_edge_unique("a", "e", ()));
+
+        let mut square = Graph::undirected();
+        for (from, to) in [(1, 2), (2, 3), (3, 4), (4, 1)] {
+            square.add_edge(from, to, ());
+        }
+        let coloring = square.bipartition().unwrap();
+        assert_ne!(coloring[0], coloring[1]);
+        assert_eq!(coloring[0], coloring[2]);
+        assert_eq!(square.undirected_neighbors(0).count(), 2);
+    }
+}
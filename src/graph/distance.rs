@@ -0,0 +1,316 @@
+use crate::error::Error;
+use crate::graph::adjacency::Graph;
+use crate::graph::MaybeProcessed::Processed;
+use crate::graph::{Bfs, Dijkstra, NeighborFn};
+use crate::hash::{FastMap, FastSet};
+use crate::set::{BoolSpace, Map};
+use crate::space::space2d::{Board2d, Direction};
+use crate::space::{Dimension, Pos, Space, SpaceMut};
+use crate::utils::ures;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Add;
+
+/// Dense matrix where `matrix[from][to]` is the shortest distance, `None` when unreachable.
+pub type DistanceMatrix<W> = Vec<Vec<Option<W>>>;
+
+/// Floyd–Warshall over a matrix of direct edge weights, every node reaches itself at `zero`.
+pub fn floyd_warshall<W>(mut matrix: DistanceMatrix<W>, zero: W) -> DistanceMatrix<W>
+where
+    W: Ord + Copy + Add<Output = W>,
+{
+    let n = matrix.len();
+    for (id, row) in matrix.iter_mut().enumerate() {
+        row[id] = Some(row[id].map_or(zero, |weight| weight.min(zero)));
+    }
+    for k in 0..n {
+        let row_k = matrix[k].clone();
+        for row in matrix.iter_mut() {
+            let Some(to_k) = row[k] else {
+                continue;
+            };
+            row.iter_mut()
+                .zip(row_k.iter())
+                .filter_map(|(to, from_k)| from_k.map(|from_k| (to, to_k + from_k)))
+                .for_each(|(to, weight)| {
+                    if to.is_none_or(|to| weight < to) {
+                        *to = Some(weight);
+                    }
+                });
+        }
+    }
+    matrix
+}
+
+#[allow(dead_code)]
+impl<N: Eq + Hash, E> Graph<N, E> {
+    pub fn floyd_warshall(&self, weight: impl Fn(&E) -> ures) -> DistanceMatrix<ures> {
+        let mut matrix = vec![vec![None; self.len()]; self.len()];
+        for from in self.node_ids() {
+            for (to, edge) in self.edges(from) {
+                let weight = weight(edge);
+                let current: &mut Option<ures> = &mut matrix[from][*to];
+                if current.is_none_or(|current| weight < current) {
+                    *current = Some(weight);
+                }
+            }
+        }
+        floyd_warshall(matrix, 0)
+    }
+}
+
+/// Runs [`Dijkstra`] from every state of `states`, `matrix[i][j]` is the distance from
+/// `states[i]` to `states[j]`. States outside `states` are still walked through.
+#[allow(dead_code)]
+pub fn dijkstra_from_every_source<State, Weight, NeighborFnObj>(
+    states: &[State],
+    zero: Weight,
+    neighbor_fn: &mut NeighborFnObj,
+) -> Result<DistanceMatrix<Weight>, Error>
+where
+    State: Eq + Hash + Clone,
+    Weight: Ord + Clone,
+    NeighborFnObj: NeighborFn<(State, Weight, ())>,
+{
+    dijkstra_from_every_source_with_map(states, zero, neighbor_fn, FastMap::default())
+}
+
+/// [`dijkstra_from_every_source`] looking up the row of each state in `index`, an empty map.
+#[allow(dead_code)]
+pub fn dijkstra_from_every_source_with_map<State, Weight, NeighborFnObj, M>(
+    states: &[State],
+    zero: Weight,
+    neighbor_fn: &mut NeighborFnObj,
+    index: M,
+) -> Result<DistanceMatrix<Weight>, Error>
+where
+    State: Eq + Hash + Clone,
+    Weight: Ord + Clone,
+    NeighborFnObj: NeighborFn<(State, Weight, ())>,
+    M: Map<State, usize>,
+{
+    let index = index_of(states, index)?;
+    states
+        .iter()
+        .map(|source| {
+            let mut row = vec![None; states.len()];
+            let mut dijkstra = Dijkstra {
+                queue: BinaryHeap::new(),
+                neighbor_fn: &mut *neighbor_fn,
+                visited: FastSet::default(),
+            };
+            dijkstra.push_queue((source.clone(), zero.clone(), ()));
+            for res in dijkstra {
+                if let Processed((state, weight, _)) = res? {
+                    if let Ok(Some(&id)) = index.get(&state) {
+                        row[id] = Some(weight);
+                    }
+                }
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+/// Runs [`Bfs`] from every state of `states` where the metadata is the distance walked so far,
+/// `matrix[i][j]` is the distance from `states[i]` to `states[j]`.
+#[allow(dead_code)]
+pub fn bfs_from_every_source<State, NeighborFnObj>(
+    states: &[State],
+    neighbor_fn: &mut NeighborFnObj,
+) -> Result<DistanceMatrix<ures>, Error>
+where
+    State: Eq + Hash + Clone,
+    NeighborFnObj: NeighborFn<(State, ures)>,
+{
+    bfs_from_every_source_with_map(states, neighbor_fn, FastMap::default())
+}
+
+/// [`bfs_from_every_source`] looking up the row of each state in `index`, an empty map.
+#[allow(dead_code)]
+pub fn bfs_from_every_source_with_map<State, NeighborFnObj, M>(
+    states: &[State],
+    neighbor_fn: &mut NeighborFnObj,
+    index: M,
+) -> Result<DistanceMatrix<ures>, Error>
+where
+    State: Eq + Hash + Clone,
+    NeighborFnObj: NeighborFn<(State, ures)>,
+    M: Map<State, usize>,
+{
+    let index = index_of(states, index)?;
+    states
+        .iter()
+        .map(|source| {
+            let mut row = vec![None; states.len()];
+            let mut bfs = Bfs {
+                queue: VecDeque::from([(source.clone(), 0)]),
+                neighbor_fn: &mut *neighbor_fn,
+                visited: FastSet::default(),
+            };
+            for res in bfs.by_ref() {
+                if let Processed((state, distance)) = res? {
+                    if let Ok(Some(&id)) = index.get(&state) {
+                        row[id] = Some(distance);
+                    }
+                }
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+fn index_of<State: Clone, M: Map<State, usize>>(
+    states: &[State],
+    mut index: M,
+) -> Result<M, Error> {
+    for (id, state) in states.iter().enumerate() {
+        index.insert(state.clone(), id)?;
+    }
+    Ok(index)
+}
+
+struct PassableNeighbor<'a, T, S, P> {
+    space: &'a S,
+    passable: P,
+    phantom_data: PhantomData<T>,
+}
+
+impl<T, S, P> NeighborFn<([usize; 2], usize)> for PassableNeighbor<'_, T, S, P>
+where
+    S: Space<T, usize, 2>,
+    P: FnMut(&T) -> bool,
+{
+    fn get_neighbors(
+        &mut self,
+        sm: &([usize; 2], usize),
+    ) -> impl IntoIterator<Item = ([usize; 2], usize)> {
+        let (pos, distance) = sm;
+        Direction::cardinal()
+            .iter()
+            .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+            .filter(|next| self.space.get(next).is_some_and(|val| (self.passable)(val)))
+            .map(|next| (next, distance + 1))
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Cardinal walking distance from `source` to every cell, `None` for cells that are not
+/// `passable` or cannot be reached.
+pub fn distance_field<T, S, P>(
+    space: &S,
+    source: [usize; 2],
+    passable: P,
+) -> Result<Board2d<Option<usize>>, Error>
+where
+    S: Space<T, usize, 2> + Dimension<2>,
+    P: FnMut(&T) -> bool,
+{
+    let [width, height] = space.dimension();
+    let mut field = Board2d::from(vec![vec![None; width]; height]);
+    let mut bfs = Bfs {
+        queue: VecDeque::from([(source, 0)]),
+        neighbor_fn: PassableNeighbor {
+            space,
+            passable,
+            phantom_data: PhantomData,
+        },
+        visited: BoolSpace::from(Board2d::from(vec![vec![false; width]; height])),
+    };
+    if !space
+        .get(&source)
+        .is_some_and(|val| (bfs.neighbor_fn.passable)(val))
+    {
+        return Ok(field);
+    }
+    for res in bfs.by_ref() {
+        if let Processed((pos, distance)) = res? {
+            field.set(&pos, Some(distance));
+        }
+    }
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::adjacency::Graph;
+    use crate::graph::distance::{
+        bfs_from_every_source, bfs_from_every_source_with_map, dijkstra_from_every_source,
+        distance_field,
+    };
+    use crate::graph::NeighborFn;
+    use crate::space::space2d::RefBoard2d;
+    use crate::space::Space;
+    use crate::utils::ures;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    struct Ring(usize);
+
+    impl NeighborFn<(usize, ures, ())> for Ring {
+        fn get_neighbors(
+            &mut self,
+            swm: &(usize, ures, ()),
+        ) -> impl IntoIterator<Item = (usize, ures, ())> {
+            let &(state, weight, _) = swm;
+            [((state + 1) % self.0, weight + 1, ())]
+        }
+    }
+
+    impl NeighborFn<(usize, ures)> for Ring {
+        fn get_neighbors(&mut self, sm: &(usize, ures)) -> impl IntoIterator<Item = (usize, ures)> {
+            let &(state, distance) = sm;
+            [((state + 1) % self.0, distance + 1)]
+        }
+    }
+
+    #[test]
+    pub fn all_pairs() -> Result<(), Error> {
+        let mut graph = Graph::directed();
+        for from in 0..6 {
+            graph.add_edge(from, (from + 1) % 6, 1);
+        }
+        graph.add_edge(0, 3, 1);
+        let floyd = graph.floyd_warshall(|weight| *weight);
+        assert_eq!(floyd[0][4], Some(2));
+        assert_eq!(floyd[4][3], Some(3));
+        assert_eq!(floyd[3][0], Some(3));
+        assert_eq!(floyd[2][2], Some(0));
+
+        let states = [0, 2, 4];
+        let dijkstra = dijkstra_from_every_source(&states, 0, &mut Ring(6))?;
+        let bfs = bfs_from_every_source(&states, &mut Ring(6))?;
+        assert_eq!(dijkstra, bfs);
+        assert_eq!(
+            bfs_from_every_source_with_map(&states, &mut Ring(6), HashMap::new())?,
+            bfs
+        );
+        assert_eq!(
+            bfs,
+            [
+                [Some(0), Some(2), Some(4)],
+                [Some(4), Some(0), Some(2)],
+                [Some(2), Some(4), Some(0)]
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn field() -> Result<(), Error> {
+        let board: RefBoard2d<'_, u8> = "..#.\n.##.\n....\n"
+            .lines()
+            .map(|line| Cow::Borrowed(line.as_bytes()))
+            .collect::<Vec<_>>()
+            .into();
+        let field = distance_field(&board, [0, 0], |val| *val == b'.')?;
+        assert_eq!(field.get(&[3, 0]), Some(&Some(7)));
+        assert_eq!(field.get(&[2, 0]), Some(&None));
+        assert_eq!(field.get(&[1, 2]), Some(&Some(3)));
+        let blocked = distance_field(&board, [2, 0], |val| *val == b'.')?;
+        assert_eq!(blocked.get(&[0, 0]), Some(&None));
+        Ok(())
+    }
+}
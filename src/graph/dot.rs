@@ -0,0 +1,211 @@
+use crate::error::Error;
+use crate::graph::adjacency::Graph;
+use crate::graph::predecessor::ParentItem;
+use crate::graph::MaybeProcessed;
+use crate::graph::MaybeProcessed::{Processed, Skipped};
+use crate::hash::FastMap;
+use crate::set::Map;
+use std::fmt::{Debug, Display, Write};
+use std::hash::Hash;
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[allow(dead_code)]
+impl<N: Eq + Hash, E> Graph<N, E> {
+    pub fn to_dot(&self) -> String
+    where
+        N: Display,
+    {
+        self.to_dot_with(|node| node.to_string(), |_| None)
+    }
+
+    /// Undirected edges are written once, from the lower to the higher node id.
+    pub fn to_dot_with(
+        &self,
+        node_label: impl Fn(&N) -> String,
+        edge_label: impl Fn(&E) -> Option<String>,
+    ) -> String {
+        let (kind, connector) = if self.is_directed() {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut res = format!("{} {{\n", kind);
+        for id in self.node_ids() {
+            let label = escape(&node_label(self.node(id).unwrap()));
+            writeln!(res, "    {} [label=\"{}\"];", id, label).unwrap();
+        }
+        for from in self.node_ids() {
+            for (to, weight) in self.edges(from) {
+                if !self.is_directed() && *to < from {
+                    continue;
+                }
+                write!(res, "    {} {} {}", from, connector, to).unwrap();
+                if let Some(label) = edge_label(weight) {
+                    write!(res, " [label=\"{}\"]", escape(&label)).unwrap();
+                }
+                res.push_str(";\n");
+            }
+        }
+        res.push_str("}\n");
+        res
+    }
+}
+
+/// Explored tree of a search, fed with its items in the order they are yielded. Processed states
+/// are nodes joined to the state they were processed from, skipped items are dashed edges. Node
+/// ids are kept in any [`Map`] of the states.
+#[derive(Clone, Debug)]
+pub struct SearchTree<State, Weight, M = FastMap<State, usize>> {
+    ids: M,
+    nodes: Vec<(State, Weight)>,
+    edges: Vec<(State, State, Weight, bool)>,
+}
+
+impl<State, Weight> Default for SearchTree<State, Weight> {
+    fn default() -> Self {
+        Self {
+            ids: FastMap::default(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<State, Weight, M> SearchTree<State, Weight, M>
+where
+    State: Clone,
+    Weight: Clone,
+    M: Map<State, usize>,
+{
+    /// `map` should start empty.
+    pub fn with_map(map: M) -> Self {
+        Self {
+            ids: map,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn record<I: ParentItem<State, Weight>>(
+        &mut self,
+        item: &MaybeProcessed<I>,
+    ) -> Result<(), Error> {
+        let (inner, processed) = match item {
+            Processed(inner) => (inner, true),
+            Skipped(inner) => (inner, false),
+        };
+        let state = inner.state().clone();
+        if processed {
+            self.ids.insert(state.clone(), self.nodes.len())?;
+            self.nodes.push((state.clone(), inner.weight().clone()));
+        }
+        if let Some(parent) = inner.parent() {
+            self.edges
+                .push((parent.clone(), state, inner.weight().clone(), processed));
+        }
+        Ok(())
+    }
+
+    pub fn to_dot(&self) -> String
+    where
+        State: Debug,
+        Weight: Debug,
+    {
+        self.to_dot_with(
+            |state| format!("{:?}", state),
+            |weight| Some(format!("{:?}", weight)),
+        )
+    }
+
+    pub fn to_dot_with(
+        &self,
+        state_label: impl Fn(&State) -> String,
+        weight_label: impl Fn(&Weight) -> Option<String>,
+    ) -> String {
+        let mut res = String::from("digraph {\n");
+        for (id, (state, weight)) in self.nodes.iter().enumerate() {
+            let mut label = state_label(state);
+            if let Some(weight) = weight_label(weight) {
+                write!(label, "\ncost {}", weight).unwrap();
+            }
+            writeln!(res, "    {} [label=\"{}\"];", id, escape(&label)).unwrap();
+        }
+        for (parent, state, weight, processed) in self.edges.iter() {
+            let (Ok(Some(from)), Ok(Some(to))) = (self.ids.get(parent), self.ids.get(state)) else {
+                continue;
+            };
+            let style = if *processed {
+                "solid"
+            } else {
+                "dashed, color=gray"
+            };
+            write!(res, "    {} -> {} [style={}", from, to, style).unwrap();
+            if let Some(weight) = weight_label(weight) {
+                write!(res, ", label=\"{}\"", escape(&weight)).unwrap();
+            }
+            res.push_str("];\n");
+        }
+        res.push_str("}\n");
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::adjacency::Graph;
+    use crate::graph::dot::SearchTree;
+    use crate::graph::MaybeProcessed::{Processed, Skipped};
+    use crate::space::space2d::Board2d;
+
+    #[test]
+    pub fn graph_to_dot() {
+        let mut graph = Graph::undirected();
+        graph.add_edge("a", "b\"", 3);
+        graph.add_edge("b\"", "c", 4);
+        assert_eq!(
+            graph.to_dot_with(|node| node.to_string(), |weight| Some(weight.to_string())),
+            "graph {\n    0 [label=\"a\"];\n    1 [label=\"b\\\"\"];\n    2 [label=\"c\"];\n    \
+             0 -- 1 [label=\"3\"];\n    1 -- 2 [label=\"4\"];\n}\n"
+        );
+
+        let mut graph = Graph::<_, ()>::directed();
+        graph.add_edge(1, 2, ());
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    0 [label=\"1\"];\n    1 [label=\"2\"];\n    0 -> 1;\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn search_tree_to_dot() -> Result<(), Error> {
+        let mut tree = SearchTree::default();
+        tree.record(&Processed(('a', 0, None)))?;
+        tree.record(&Processed(('b', 1, Some('a'))))?;
+        tree.record(&Skipped(('b', 2, Some('a'))))?;
+        assert_eq!(
+            tree.to_dot(),
+            "digraph {\n    0 [label=\"'a'\\ncost 0\"];\n    1 [label=\"'b'\\ncost 1\"];\n    \
+             0 -> 1 [style=solid, label=\"1\"];\n    \
+             0 -> 1 [style=dashed, color=gray, label=\"2\"];\n}\n"
+        );
+
+        let mut grid = SearchTree::with_map(Board2d::from(vec![vec![None; 2]; 1]));
+        grid.record(&Processed(([0, 0], (), None)))?;
+        grid.record(&Processed(([1, 0], (), Some([0, 0]))))?;
+        assert!(grid.record(&Processed(([2, 0], (), Some([1, 0])))).is_err());
+        assert_eq!(
+            grid.to_dot_with(|state| format!("{:?}", state), |_| None),
+            "digraph {\n    0 [label=\"[0, 0]\"];\n    1 [label=\"[1, 0]\"];\n    \
+             0 -> 1 [style=solid];\n}\n"
+        );
+        Ok(())
+    }
+}
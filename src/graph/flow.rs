@@ -0,0 +1,372 @@
+use crate::graph::adjacency::{Graph, NodeId};
+use crate::utils::ures;
+use bit_set::BitSet;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// Residual network where arc `i ^ 1` is the reverse of arc `i`.
+struct FlowNetwork {
+    head: Vec<Vec<usize>>,
+    to: Vec<NodeId>,
+    capacity: Vec<ures>,
+}
+
+impl FlowNetwork {
+    fn new<N: Eq + Hash, E>(graph: &Graph<N, E>, capacity: impl Fn(&E) -> ures) -> Self {
+        let mut network = Self {
+            head: vec![Vec::new(); graph.len()],
+            to: Vec::new(),
+            capacity: Vec::new(),
+        };
+        for from in graph.node_ids() {
+            for (to, weight) in graph.edges(from) {
+                network.add_arc(from, *to, capacity(weight));
+            }
+        }
+        network
+    }
+
+    fn add_arc(&mut self, from: NodeId, to: NodeId, capacity: ures) {
+        self.head[from].push(self.to.len());
+        self.to.push(to);
+        self.capacity.push(capacity);
+        self.head[to].push(self.to.len());
+        self.to.push(from);
+        self.capacity.push(0);
+    }
+
+    fn levels(&self, source: NodeId) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.head.len()];
+        level[source] = Some(0);
+        let mut work = VecDeque::from([source]);
+        while let Some(from) = work.pop_front() {
+            for &arc in self.head[from].iter() {
+                let to = self.to[arc];
+                if self.capacity[arc] > 0 && level[to].is_none() {
+                    level[to] = level[from].map(|l| l + 1);
+                    work.push_back(to);
+                }
+            }
+        }
+        level
+    }
+
+    fn dinic_push(
+        &mut self,
+        from: NodeId,
+        sink: NodeId,
+        limit: ures,
+        level: &[Option<usize>],
+        next_arc: &mut [usize],
+    ) -> ures {
+        if from == sink {
+            return limit;
+        }
+        while let Some(&arc) = self.head[from].get(next_arc[from]) {
+            let to = self.to[arc];
+            if self.capacity[arc] > 0 && level[to] == level[from].map(|l| l + 1) {
+                let pushed =
+                    self.dinic_push(to, sink, limit.min(self.capacity[arc]), level, next_arc);
+                if pushed > 0 {
+                    self.capacity[arc] -= pushed;
+                    self.capacity[arc ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next_arc[from] += 1;
+        }
+        0
+    }
+
+    fn dinic(&mut self, source: NodeId, sink: NodeId) -> ures {
+        let mut flow = 0;
+        loop {
+            let level = self.levels(source);
+            if level[sink].is_none() {
+                return flow;
+            }
+            let mut next_arc = vec![0; self.head.len()];
+            loop {
+                let pushed = self.dinic_push(source, sink, ures::MAX, &level, &mut next_arc);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+    }
+
+    fn edmonds_karp(&mut self, source: NodeId, sink: NodeId) -> ures {
+        let mut flow = 0;
+        loop {
+            let mut parent_arc = vec![None; self.head.len()];
+            let mut work = VecDeque::from([source]);
+            while let Some(from) = work.pop_front() {
+                for &arc in self.head[from].iter() {
+                    let to = self.to[arc];
+                    if self.capacity[arc] > 0 && to != source && parent_arc[to].is_none() {
+                        parent_arc[to] = Some(arc);
+                        work.push_back(to);
+                    }
+                }
+            }
+            if parent_arc[sink].is_none() {
+                return flow;
+            }
+
+            let mut pushed = ures::MAX;
+            let mut node = sink;
+            while let Some(arc) = parent_arc[node] {
+                pushed = pushed.min(self.capacity[arc]);
+                node = self.to[arc ^ 1];
+            }
+            let mut node = sink;
+            while let Some(arc) = parent_arc[node] {
+                self.capacity[arc] -= pushed;
+                self.capacity[arc ^ 1] += pushed;
+                node = self.to[arc ^ 1];
+            }
+            flow += pushed;
+        }
+    }
+
+    fn source_side(&self, source: NodeId) -> BitSet {
+        self.levels(source)
+            .iter()
+            .enumerate()
+            .filter(|(_, level)| level.is_some())
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct MinCut {
+    pub value: ures,
+    /// Nodes still reachable from the source in the residual network.
+    pub source_side: BitSet,
+    /// Edges leaving the source side, each undirected edge is listed once.
+    pub edges: Vec<(NodeId, NodeId)>,
+}
+
+#[allow(dead_code)]
+impl<N: Eq + Hash, E> Graph<N, E> {
+    /// Maximum flow by Dinic's algorithm, an undirected edge has its capacity in both directions.
+    pub fn max_flow(&self, source: NodeId, sink: NodeId, capacity: impl Fn(&E) -> ures) -> ures {
+        if source == sink {
+            return 0;
+        }
+        FlowNetwork::new(self, capacity).dinic(source, sink)
+    }
+
+    pub fn max_flow_edmonds_karp(
+        &self,
+        source: NodeId,
+        sink: NodeId,
+        capacity: impl Fn(&E) -> ures,
+    ) -> ures {
+        if source == sink {
+            return 0;
+        }
+        FlowNetwork::new(self, capacity).edmonds_karp(source, sink)
+    }
+
+    pub fn min_cut(&self, source: NodeId, sink: NodeId, capacity: impl Fn(&E) -> ures) -> MinCut {
+        let mut network = FlowNetwork::new(self, &capacity);
+        let value = if source == sink {
+            0
+        } else {
+            network.dinic(source, sink)
+        };
+        let source_side = network.source_side(source);
+        let edges = source_side
+            .iter()
+            .flat_map(|from| {
+                self.edges(from)
+                    .iter()
+                    .filter(|(to, weight)| !source_side.contains(*to) && capacity(weight) > 0)
+                    .map(move |(to, _)| (from, *to))
+            })
+            .collect();
+        MinCut {
+            value,
+            source_side,
+            edges,
+        }
+    }
+
+    /// Global minimum cut ignoring edge direction by Stoer–Wagner, returns the cut value with
+    /// the nodes of one side. `None` when there are fewer than two nodes.
+    pub fn global_min_cut(&self, capacity: impl Fn(&E) -> ures) -> Option<(ures, Vec<NodeId>)> {
+        let n = self.len();
+        if n < 2 {
+            return None;
+        }
+        let mut weights = vec![vec![0; n]; n];
+        for from in self.node_ids() {
+            for (to, weight) in self.edges(from) {
+                if from != *to {
+                    weights[from][*to] += capacity(weight);
+                    if self.is_directed() {
+                        weights[*to][from] += capacity(weight);
+                    }
+                }
+            }
+        }
+
+        let mut groups = self.node_ids().map(|id| vec![id]).collect::<Vec<_>>();
+        let mut active = self.node_ids().collect::<Vec<_>>();
+        let mut best: Option<(ures, Vec<NodeId>)> = None;
+        while active.len() > 1 {
+            let mut connectivity = vec![0; n];
+            let mut added = BitSet::with_capacity(n);
+            let mut prev = active[0];
+            for phase in 0..active.len() {
+                let next = active
+                    .iter()
+                    .copied()
+                    .filter(|id| !added.contains(*id))
+                    .max_by_key(|id| connectivity[*id])
+                    .unwrap();
+                added.insert(next);
+                if phase + 1 == active.len() {
+                    if best
+                        .as_ref()
+                        .is_none_or(|(value, _)| connectivity[next] < *value)
+                    {
+                        best = Some((connectivity[next], groups[next].clone()));
+                    }
+                    let merged = std::mem::take(&mut groups[next]);
+                    groups[prev].extend(merged);
+                    let next_weights = std::mem::take(&mut weights[next]);
+                    weights[prev]
+                        .iter_mut()
+                        .zip(next_weights)
+                        .for_each(|(weight, next_weight)| *weight += next_weight);
+                    let prev_weights = weights[prev].clone();
+                    weights
+                        .iter_mut()
+                        .zip(prev_weights)
+                        .filter(|(row, _)| !row.is_empty())
+                        .for_each(|(row, weight)| row[prev] = weight);
+                    active.retain(|id| *id != next);
+                } else {
+                    active
+                        .iter()
+                        .for_each(|id| connectivity[*id] += weights[next][*id]);
+                    prev = next;
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::adjacency::Graph;
+    use crate::utils::ures;
+
+    fn random_graph(seed: &mut u64, n: usize, edges: usize, directed: bool) -> Graph<usize, ures> {
+        let mut next = || {
+            *seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (*seed >> 33) as usize
+        };
+        let mut graph = if directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        (0..n).for_each(|id| {
+            graph.intern(id);
+        });
+        for _ in 0..edges {
+            let (from, to, capacity) = (next() % n, next() % n, next() % 10);
+            if from != to {
+                graph.add_edge(from, to, capacity as ures);
+            }
+        }
+        graph
+    }
+
+    fn cut_value(graph: &Graph<usize, ures>, side: impl Fn(usize) -> bool) -> ures {
+        graph
+            .node_ids()
+            .filter(|from| side(*from))
+            .flat_map(|from| graph.edges(from).iter())
+            .filter(|(to, _)| !side(*to))
+            .map(|(_, capacity)| *capacity)
+            .sum()
+    }
+
+    #[test]
+    pub fn max_flow_is_min_cut() {
+        let mut seed = 7;
+        for directed in [true, false] {
+            for _ in 0..50 {
+                let graph = random_graph(&mut seed, 7, 16, directed);
+                let brute_force = (0..1 << 7)
+                    .filter(|mask| mask & 1 == 1 && mask & (1 << 6) == 0)
+                    .map(|mask| cut_value(&graph, |id| mask & (1 << id) != 0))
+                    .min()
+                    .unwrap();
+                assert_eq!(graph.max_flow(0, 6, |c| *c), brute_force);
+                assert_eq!(graph.max_flow_edmonds_karp(0, 6, |c| *c), brute_force);
+
+                let cut = graph.min_cut(0, 6, |c| *c);
+                assert_eq!(cut.value, brute_force);
+                assert!(cut.source_side.contains(0) && !cut.source_side.contains(6));
+                assert_eq!(
+                    cut_value(&graph, |id| cut.source_side.contains(id)),
+                    brute_force
+                );
+                assert!(cut.edges.iter().all(|(from, to)| {
+                    cut.source_side.contains(*from) && !cut.source_side.contains(*to)
+                }));
+            }
+        }
+    }
+
+    #[test]
+    pub fn global_min_cut() {
+        let mut seed = 11;
+        for _ in 0..50 {
+            let graph = random_graph(&mut seed, 7, 14, false);
+            let brute_force = (1..(1 << 7) - 1)
+                .map(|mask| cut_value(&graph, |id| mask & (1 << id) != 0))
+                .min()
+                .unwrap();
+            let (value, side) = graph.global_min_cut(|c| *c).unwrap();
+            assert_eq!(value, brute_force);
+            assert!(!side.is_empty() && side.len() < 7);
+            assert_eq!(cut_value(&graph, |id| side.contains(&id)), brute_force);
+        }
+    }
+
+    #[test]
+    pub fn cut_three_wires() {
+        let mut graph = Graph::undirected();
+        for cluster in [["a", "b", "c", "d", "i"], ["e", "f", "g", "h", "j"]] {
+            for (i, from) in cluster.iter().enumerate() {
+                for to in cluster[i + 1..].iter() {
+                    graph.add_edge(*from, *to, ());
+                }
+            }
+        }
+        for (from, to) in [("a", "e"), ("b", "f"), ("c", "g")] {
+            graph.add_edge(from, to, ());
+        }
+        let (value, side) = graph.global_min_cut(|_| 1).unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(side.len(), 5);
+
+        let a = graph.node_id(&"a").unwrap();
+        let h = graph.node_id(&"h").unwrap();
+        let cut = graph.min_cut(a, h, |_| 1);
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.edges.len(), 3);
+    }
+}
@@ -0,0 +1,767 @@
+use crate::error::Error;
+use crate::graph::MaybeProcessed::{Processed, Skipped};
+use crate::set::Set;
+use crate::utils::{musize, ures};
+use derive_more::{From, Into};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Add;
+
+pub mod adjacency;
+pub mod distance;
+pub mod dot;
+pub mod flow;
+pub mod predecessor;
+
+#[derive(From, Into)]
+pub struct StateWithWeightAndMetadata<S, W: Ord, M>(S, W, M);
+
+impl<S, W: Ord, M> Eq for StateWithWeightAndMetadata<S, W, M> {}
+impl<S, W: Ord, M> PartialEq<Self> for StateWithWeightAndMetadata<S, W, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.1.eq(&other.1)
+    }
+}
+
+impl<S, W: Ord, M> PartialOrd<Self> for StateWithWeightAndMetadata<S, W, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, W: Ord, M> Ord for StateWithWeightAndMetadata<S, W, M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.1.cmp(&other.1)
+    }
+}
+
+pub enum MaybeProcessed<T> {
+    Processed(T),
+    Skipped(T),
+}
+
+impl<T> MaybeProcessed<T> {
+    pub fn inner(&self) -> &T {
+        match self {
+            Processed(inner) | Skipped(inner) => inner,
+        }
+    }
+}
+
+pub struct Dijkstra<State, Weight, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    Weight: Ord,
+    VisitedStateSet: Set<State>,
+{
+    pub queue: BinaryHeap<Reverse<StateWithWeightAndMetadata<State, Weight, Metadata>>>,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+}
+
+impl<State, Weight, Metadata, VisitedStateSet, NeighborFnObj> Iterator
+    for Dijkstra<State, Weight, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    State: Clone,
+    Weight: Ord,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, Weight, Metadata)>,
+{
+    type Item = Result<MaybeProcessed<(State, Weight, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(Reverse(state_weight_metadata)) = self.queue.pop() {
+            let swm = state_weight_metadata.into();
+            let (state, _, _) = &swm;
+            match self.visited.insert(state.clone()) {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => return Some(Ok(Skipped(swm))),
+                Ok(true) => self
+                    .neighbor_fn
+                    .get_neighbors(&swm)
+                    .into_iter()
+                    .map(StateWithWeightAndMetadata::from)
+                    .map(Reverse)
+                    .for_each(|swm| self.queue.push(swm)),
+            }
+
+            return Some(Ok(Processed(swm)));
+        }
+
+        None
+    }
+}
+
+impl<State, Weight, Metadata, VisitedStateSet, NeighborFnObj>
+    Dijkstra<State, Weight, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    Weight: Ord,
+    VisitedStateSet: Set<State>,
+{
+    pub fn push_queue(&mut self, swm: (State, Weight, Metadata)) {
+        self.queue
+            .push(Reverse(StateWithWeightAndMetadata::from(swm)))
+    }
+}
+
+/// Queue entry ordered by `weight + estimate`.
+type AStarEntry<State, Weight, Metadata> =
+    StateWithWeightAndMetadata<(State, Weight, Metadata), Weight, ()>;
+
+pub struct AStar<State, Weight, Metadata, VisitedStateSet, NeighborFnObj, HeuristicObj>
+where
+    Weight: Ord,
+    VisitedStateSet: Set<State>,
+{
+    pub queue: BinaryHeap<Reverse<AStarEntry<State, Weight, Metadata>>>,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+    pub heuristic: HeuristicObj,
+}
+
+impl<State, Weight, Metadata, VisitedStateSet, NeighborFnObj, HeuristicObj> Iterator
+    for AStar<State, Weight, Metadata, VisitedStateSet, NeighborFnObj, HeuristicObj>
+where
+    State: Clone,
+    Weight: Ord + Clone + Add<Output = Weight>,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, Weight, Metadata)>,
+    HeuristicObj: Heuristic<State, Weight>,
+{
+    type Item = Result<MaybeProcessed<(State, Weight, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(Reverse(StateWithWeightAndMetadata(swm, priority, _))) = self.queue.pop() {
+            let (state, _, _) = &swm;
+            match self.visited.insert(state.clone()) {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => return Some(Ok(Skipped(swm))),
+                Ok(true) => {
+                    for next_swm in self.neighbor_fn.get_neighbors(&swm) {
+                        let (next_state, next_weight, _) = &next_swm;
+                        let next_priority =
+                            next_weight.clone() + self.heuristic.estimate(next_state);
+                        // A consistent heuristic never decreases `weight + estimate` along an edge,
+                        // otherwise processed states are not guaranteed to be optimal.
+                        if cfg!(debug_assertions) && next_priority < priority {
+                            return Some(Err(Error::InvalidState(
+                                "inconsistent heuristic: estimate decreased more than edge weight"
+                                    .into(),
+                            )));
+                        }
+                        self.queue.push(Reverse(StateWithWeightAndMetadata(
+                            next_swm,
+                            next_priority,
+                            (),
+                        )));
+                    }
+                }
+            }
+
+            return Some(Ok(Processed(swm)));
+        }
+
+        None
+    }
+}
+
+impl<State, Weight, Metadata, VisitedStateSet, NeighborFnObj, HeuristicObj>
+    AStar<State, Weight, Metadata, VisitedStateSet, NeighborFnObj, HeuristicObj>
+where
+    Weight: Ord + Clone + Add<Output = Weight>,
+    VisitedStateSet: Set<State>,
+    HeuristicObj: Heuristic<State, Weight>,
+{
+    pub fn push_queue(&mut self, swm: (State, Weight, Metadata)) {
+        let (state, weight, _) = &swm;
+        let priority = weight.clone() + self.heuristic.estimate(state);
+        self.queue
+            .push(Reverse(StateWithWeightAndMetadata(swm, priority, ())))
+    }
+}
+
+/// Dijkstra for edges weighing `0` or `1`, a neighbor must weigh the same or one more than the
+/// state it comes from.
+#[allow(dead_code)]
+pub struct ZeroOneBfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    VisitedStateSet: Set<State>,
+{
+    pub queue: VecDeque<(State, ures, Metadata)>,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+}
+
+impl<State, Metadata, VisitedStateSet, NeighborFnObj> Iterator
+    for ZeroOneBfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    State: Clone,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, ures, Metadata)>,
+{
+    type Item = Result<MaybeProcessed<(State, ures, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(swm) = self.queue.pop_front() {
+            let (state, weight, _) = &swm;
+            match self.visited.insert(state.clone()) {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => return Some(Ok(Skipped(swm))),
+                Ok(true) => {
+                    for next_swm in self.neighbor_fn.get_neighbors(&swm) {
+                        let (_, next_weight, _) = &next_swm;
+                        if next_weight == weight {
+                            self.queue.push_front(next_swm);
+                        } else if *next_weight == weight + 1 {
+                            self.queue.push_back(next_swm);
+                        } else {
+                            return Some(Err(Error::InvalidState(
+                                "0-1 bfs edge weight must be 0 or 1".into(),
+                            )));
+                        }
+                    }
+                }
+            }
+
+            return Some(Ok(Processed(swm)));
+        }
+
+        None
+    }
+}
+
+/// Dial's algorithm, Dijkstra with one bucket per weight starting from `base`. Suited for small
+/// integer edge weights as every weight between `base` and the heaviest queued one has a bucket.
+pub struct Dial<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    VisitedStateSet: Set<State>,
+{
+    pub buckets: VecDeque<Vec<(State, ures, Metadata)>>,
+    pub base: ures,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+}
+
+impl<State, Metadata, VisitedStateSet, NeighborFnObj> Iterator
+    for Dial<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    State: Clone,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, ures, Metadata)>,
+{
+    type Item = Result<MaybeProcessed<(State, ures, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let swm = loop {
+            match self.buckets.front_mut()?.pop() {
+                Some(swm) => break swm,
+                None => {
+                    self.buckets.pop_front();
+                    self.base += 1;
+                }
+            }
+        };
+
+        let (state, _, _) = &swm;
+        match self.visited.insert(state.clone()) {
+            Err(e) => return Some(Err(e)),
+            Ok(false) => return Some(Ok(Skipped(swm))),
+            Ok(true) => {
+                for next_swm in self.neighbor_fn.get_neighbors(&swm) {
+                    if let Err(e) = push_bucket(&mut self.buckets, self.base, next_swm) {
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+
+        Some(Ok(Processed(swm)))
+    }
+}
+
+impl<State, Metadata, VisitedStateSet, NeighborFnObj>
+    Dial<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    VisitedStateSet: Set<State>,
+{
+    pub fn push_queue(&mut self, swm: (State, ures, Metadata)) -> Result<(), Error> {
+        push_bucket(&mut self.buckets, self.base, swm)
+    }
+}
+
+fn push_bucket<State, Metadata>(
+    buckets: &mut VecDeque<Vec<(State, ures, Metadata)>>,
+    base: ures,
+    swm: (State, ures, Metadata),
+) -> Result<(), Error> {
+    let (_, weight, _) = &swm;
+    let idx = weight
+        .checked_sub(base)
+        .ok_or_else(|| Error::InvalidState("dial weight is lower than base".into()))?
+        as musize;
+    if buckets.len() <= idx {
+        buckets.resize_with(idx + 1, Vec::new);
+    }
+    buckets[idx].push(swm);
+    Ok(())
+}
+
+/// Shortest distance from `start` to `goal` expanding whole levels from whichever side has the
+/// smaller frontier. `backward` must walk the edges of `forward` in reverse.
+#[allow(dead_code)]
+pub fn bidirectional_bfs<State, ForwardFn, BackwardFn, ForwardSet, BackwardSet>(
+    start: State,
+    goal: State,
+    forward: &mut ForwardFn,
+    backward: &mut BackwardFn,
+    mut forward_visited: ForwardSet,
+    mut backward_visited: BackwardSet,
+) -> Result<Option<ures>, Error>
+where
+    State: Clone + PartialEq,
+    ForwardFn: NeighborFn<(State, ())>,
+    BackwardFn: NeighborFn<(State, ())>,
+    ForwardSet: Set<State>,
+    BackwardSet: Set<State>,
+{
+    if start == goal {
+        return Ok(Some(0));
+    }
+    forward_visited.insert(start.clone())?;
+    backward_visited.insert(goal.clone())?;
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![goal];
+    let mut distance = 0;
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        distance += 1;
+        let met = if forward_frontier.len() <= backward_frontier.len() {
+            expand_level(
+                &mut forward_frontier,
+                forward,
+                &mut forward_visited,
+                &backward_visited,
+            )?
+        } else {
+            expand_level(
+                &mut backward_frontier,
+                backward,
+                &mut backward_visited,
+                &forward_visited,
+            )?
+        };
+        if met {
+            return Ok(Some(distance));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Replaces `frontier` with its unvisited neighbors, returns whether one was seen by the other
+/// side. Both sides stop one level short of each other before, so meeting means the distance is
+/// exactly the sum of both levels.
+fn expand_level<State, NeighborFnObj, VisitedStateSet, OtherSet>(
+    frontier: &mut Vec<State>,
+    neighbor_fn: &mut NeighborFnObj,
+    visited: &mut VisitedStateSet,
+    other_visited: &OtherSet,
+) -> Result<bool, Error>
+where
+    State: Clone,
+    NeighborFnObj: NeighborFn<(State, ())>,
+    VisitedStateSet: Set<State>,
+    OtherSet: Set<State>,
+{
+    let mut next_frontier = Vec::new();
+    for state in frontier.drain(..) {
+        for (next, _) in neighbor_fn.get_neighbors(&(state, ())) {
+            if other_visited.contains(&next)? {
+                return Ok(true);
+            }
+            if visited.insert(next.clone())? {
+                next_frontier.push(next);
+            }
+        }
+    }
+    *frontier = next_frontier;
+    Ok(false)
+}
+
+pub struct Bfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    VisitedStateSet: Set<State>,
+{
+    pub queue: VecDeque<(State, Metadata)>,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+}
+
+impl<State, Metadata, VisitedStateSet, NeighborFnObj> Iterator
+    for Bfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    State: Clone,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, Metadata)>,
+{
+    type Item = Result<MaybeProcessed<(State, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(state_weight_metadata) = self.queue.pop_front() {
+            let (state, _) = &state_weight_metadata;
+            match self.visited.insert(state.clone()) {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => return Some(Ok(Skipped(state_weight_metadata))),
+                Ok(true) => self
+                    .neighbor_fn
+                    .get_neighbors(&state_weight_metadata)
+                    .into_iter()
+                    .for_each(|swm| self.queue.push_back(swm)),
+            }
+
+            return Some(Ok(Processed(state_weight_metadata)));
+        }
+
+        None
+    }
+}
+
+pub struct Dfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    VisitedStateSet: Set<State>,
+{
+    pub queue: Vec<(State, Metadata)>,
+    pub neighbor_fn: NeighborFnObj,
+    pub visited: VisitedStateSet,
+}
+
+impl<State, Metadata, VisitedStateSet, NeighborFnObj> Iterator
+    for Dfs<State, Metadata, VisitedStateSet, NeighborFnObj>
+where
+    State: Clone,
+    VisitedStateSet: Set<State>,
+    NeighborFnObj: NeighborFn<(State, Metadata)>,
+{
+    type Item = Result<MaybeProcessed<(State, Metadata)>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(state_weight_metadata) = self.queue.pop() {
+            let (state, _) = &state_weight_metadata;
+            match self.visited.insert(state.clone()) {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => return Some(Ok(Skipped(state_weight_metadata))),
+                Ok(true) => self
+                    .neighbor_fn
+                    .get_neighbors(&state_weight_metadata)
+                    .into_iter()
+                    .for_each(|swm| self.queue.push(swm)),
+            }
+
+            return Some(Ok(Processed(state_weight_metadata)));
+        }
+
+        None
+    }
+}
+
+pub trait NeighborFn<T> {
+    fn get_neighbors(&mut self, state: &T) -> impl IntoIterator<Item = T>;
+}
+
+impl<T, F: NeighborFn<T>> NeighborFn<T> for &mut F {
+    fn get_neighbors(&mut self, state: &T) -> impl IntoIterator<Item = T> {
+        (**self).get_neighbors(state)
+    }
+}
+
+pub trait Heuristic<State, Weight> {
+    fn estimate(&self, state: &State) -> Weight;
+}
+
+impl<State, Weight, F: Fn(&State) -> Weight> Heuristic<State, Weight> for F {
+    fn estimate(&self, state: &State) -> Weight {
+        self(state)
+    }
+}
+
+#[derive(Debug, Clone, Copy, From)]
+pub struct Manhattan<const N: usize> {
+    pub goal: [usize; N],
+}
+
+impl<const N: usize> Heuristic<[usize; N], ures> for Manhattan<N> {
+    fn estimate(&self, state: &[usize; N]) -> ures {
+        state
+            .iter()
+            .zip(self.goal.iter())
+            .map(|(a, b)| a.abs_diff(*b) as ures)
+            .sum()
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, From)]
+pub struct Chebyshev<const N: usize> {
+    pub goal: [usize; N],
+}
+
+impl<const N: usize> Heuristic<[usize; N], ures> for Chebyshev<N> {
+    fn estimate(&self, state: &[usize; N]) -> ures {
+        state
+            .iter()
+            .zip(self.goal.iter())
+            .map(|(a, b)| a.abs_diff(*b) as ures)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::MaybeProcessed::Processed;
+    use crate::graph::{
+        bidirectional_bfs, AStar, Bfs, Chebyshev, Dial, Dijkstra, Heuristic, Manhattan, NeighborFn,
+        ZeroOneBfs,
+    };
+    use crate::space::Pos;
+    use crate::utils::{neighbors8, ures};
+    use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+    struct OpenGrid<const DIAGONAL: bool>(usize);
+
+    impl<const DIAGONAL: bool> NeighborFn<([usize; 2], ures, ())> for OpenGrid<DIAGONAL> {
+        fn get_neighbors(
+            &mut self,
+            swm: &([usize; 2], ures, ()),
+        ) -> impl IntoIterator<Item = ([usize; 2], ures, ())> {
+            let (pos, cost, _) = swm;
+            let size = self.0;
+            let neighbors: Vec<_> = if DIAGONAL {
+                neighbors8(pos).collect()
+            } else {
+                [[-1, 0], [1, 0], [0, -1], [0, 1]]
+                    .iter()
+                    .filter_map(|diff| pos.shift(diff))
+                    .collect()
+            };
+            neighbors
+                .into_iter()
+                .filter(move |[x, y]| *x < size && *y < size)
+                .map(move |next| (next, *cost + 1, ()))
+        }
+    }
+
+    fn shortest<const DIAGONAL: bool, H: Heuristic<[usize; 2], ures>>(
+        heuristic: H,
+        goal: [usize; 2],
+    ) -> Result<Option<(ures, usize)>, Error> {
+        let mut a_star = AStar {
+            queue: BinaryHeap::new(),
+            neighbor_fn: OpenGrid::<DIAGONAL>(10),
+            visited: HashSet::new(),
+            heuristic,
+        };
+        a_star.push_queue(([0, 0], 0, ()));
+        let mut processed = 0;
+        for res in a_star {
+            if let Processed((state, cost, _)) = res? {
+                processed += 1;
+                if state == goal {
+                    return Ok(Some((cost, processed)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    #[test]
+    pub fn a_star() -> Result<(), Error> {
+        let goal = [9, 6];
+        let (cost, processed) = shortest::<false, _>(Manhattan::from(goal), goal)?.unwrap();
+        let (dijkstra_cost, dijkstra_processed) =
+            shortest::<false, _>(|_: &[usize; 2]| 0 as ures, goal)?.unwrap();
+        assert_eq!(cost, 15);
+        assert_eq!(cost, dijkstra_cost);
+        assert!(processed < dijkstra_processed);
+
+        let (cost, _) = shortest::<true, _>(Chebyshev::from(goal), goal)?.unwrap();
+        assert_eq!(cost, 9);
+        Ok(())
+    }
+
+    #[test]
+    pub fn a_star_inconsistent_heuristic() {
+        let goal = [9, 9];
+        // Manhattan over-estimates when diagonal moves are allowed
+        let res = shortest::<true, _>(Manhattan::from(goal), goal);
+        if cfg!(debug_assertions) {
+            assert!(res.is_err());
+        }
+    }
+
+    /// Grid where moving onto `[x, y]` costs `(x * 7 + y * 3) % modulo`, walls where it is `0`
+    /// when `walls` is set.
+    struct WeightedGrid {
+        size: usize,
+        modulo: usize,
+        walls: bool,
+    }
+
+    impl WeightedGrid {
+        fn neighbors(&self, pos: [usize; 2]) -> impl Iterator<Item = ([usize; 2], ures)> + '_ {
+            [[-1, 0], [1, 0], [0, -1], [0, 1]]
+                .into_iter()
+                .filter_map(move |diff| pos.shift(&diff))
+                .filter(|[x, y]| *x < self.size && *y < self.size)
+                .map(|[x, y]| ([x, y], ((x * 7 + y * 3) % self.modulo) as ures))
+                .filter(|(_, cost)| !self.walls || *cost != 0)
+        }
+    }
+
+    impl NeighborFn<([usize; 2], ures, ())> for WeightedGrid {
+        fn get_neighbors(
+            &mut self,
+            swm: &([usize; 2], ures, ()),
+        ) -> impl IntoIterator<Item = ([usize; 2], ures, ())> {
+            let (pos, weight, _) = swm;
+            self.neighbors(*pos)
+                .map(|(next, cost)| (next, weight + cost, ()))
+                .collect::<Vec<_>>()
+        }
+    }
+
+    impl NeighborFn<([usize; 2], ())> for WeightedGrid {
+        fn get_neighbors(
+            &mut self,
+            sm: &([usize; 2], ()),
+        ) -> impl IntoIterator<Item = ([usize; 2], ())> {
+            let (pos, _) = sm;
+            self.neighbors(*pos)
+                .map(|(next, _)| (next, ()))
+                .collect::<Vec<_>>()
+        }
+    }
+
+    fn dijkstra_distances(grid: WeightedGrid) -> Result<HashMap<[usize; 2], ures>, Error> {
+        let mut dijkstra = Dijkstra {
+            queue: BinaryHeap::new(),
+            neighbor_fn: grid,
+            visited: HashSet::new(),
+        };
+        dijkstra.push_queue(([0, 0], 0, ()));
+        let mut res = HashMap::new();
+        for item in dijkstra {
+            if let Processed((state, weight, _)) = item? {
+                res.insert(state, weight);
+            }
+        }
+        Ok(res)
+    }
+
+    #[test]
+    pub fn zero_one_bfs_and_dial() -> Result<(), Error> {
+        let expected = dijkstra_distances(WeightedGrid {
+            size: 12,
+            modulo: 2,
+            walls: false,
+        })?;
+        let mut zero_one = ZeroOneBfs {
+            queue: VecDeque::from([([0, 0], 0, ())]),
+            neighbor_fn: WeightedGrid {
+                size: 12,
+                modulo: 2,
+                walls: false,
+            },
+            visited: HashSet::new(),
+        };
+        let mut res = HashMap::new();
+        for item in zero_one.by_ref() {
+            if let Processed((state, weight, _)) = item? {
+                res.insert(state, weight);
+            }
+        }
+        assert_eq!(res, expected);
+
+        zero_one.neighbor_fn.modulo = 3;
+        zero_one.visited.clear();
+        zero_one.queue.push_back(([0, 0], 0, ()));
+        assert!(zero_one.any(|item| item.is_err()));
+
+        let expected = dijkstra_distances(WeightedGrid {
+            size: 12,
+            modulo: 5,
+            walls: false,
+        })?;
+        let mut dial = Dial {
+            buckets: VecDeque::new(),
+            base: 0,
+            neighbor_fn: WeightedGrid {
+                size: 12,
+                modulo: 5,
+                walls: false,
+            },
+            visited: HashSet::new(),
+        };
+        dial.push_queue(([0, 0], 0, ()))?;
+        let mut res = HashMap::new();
+        for item in dial {
+            if let Processed((state, weight, _)) = item? {
+                res.insert(state, weight);
+            }
+        }
+        assert_eq!(res, expected);
+        Ok(())
+    }
+
+    #[test]
+    pub fn bidirectional() -> Result<(), Error> {
+        let size = 15;
+        let mut grid = WeightedGrid {
+            size,
+            modulo: 4,
+            walls: true,
+        };
+        let mut bfs = Bfs {
+            queue: VecDeque::from([([1, 0], ())]),
+            neighbor_fn: WeightedGrid {
+                size,
+                modulo: 4,
+                walls: true,
+            },
+            visited: HashSet::new(),
+        };
+        let mut depth = HashMap::from([([1, 0], 0)]);
+        while let Some(item) = bfs.next() {
+            if let Processed((state, _)) = item? {
+                let next_depth = depth[&state] + 1;
+                for (next, _) in bfs.neighbor_fn.neighbors(state) {
+                    depth.entry(next).or_insert(next_depth);
+                }
+            }
+        }
+
+        for x in 0..size {
+            for y in 0..size {
+                // walls cannot be entered, edges between open cells go both ways
+                if (x * 7 + y * 3) % 4 == 0 {
+                    continue;
+                }
+                let mut reverse = WeightedGrid {
+                    size,
+                    modulo: 4,
+                    walls: true,
+                };
+                let res = bidirectional_bfs(
+                    [1, 0],
+                    [x, y],
+                    &mut grid,
+                    &mut reverse,
+                    HashSet::new(),
+                    HashSet::new(),
+                )?;
+                assert_eq!(res, depth.get(&[x, y]).copied(), "{:?}", [x, y]);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+use crate::error::Error;
+use crate::graph::MaybeProcessed;
+use crate::graph::MaybeProcessed::{Processed, Skipped};
+use crate::hash::{FastMap, FastSet};
+use crate::set::Map;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Search item carrying the state it was reached from as part of its metadata, e.g.
+/// `(State, Weight, Option<State>)` for [`Dijkstra`](crate::graph::Dijkstra) or
+/// `(State, Option<State>)` for [`Bfs`](crate::graph::Bfs), where the start has no parent.
+pub trait ParentItem<State, Weight> {
+    fn state(&self) -> &State;
+    fn weight(&self) -> &Weight;
+    fn parent(&self) -> Option<&State>;
+}
+
+impl<State, Weight> ParentItem<State, Weight> for (State, Weight, Option<State>) {
+    fn state(&self) -> &State {
+        &self.0
+    }
+
+    fn weight(&self) -> &Weight {
+        &self.1
+    }
+
+    fn parent(&self) -> Option<&State> {
+        self.2.as_ref()
+    }
+}
+
+/// Unweighted items compare equal, so [`Predecessors`] only accepts them with
+/// [`PredecessorMode::Single`].
+impl<State> ParentItem<State, ()> for (State, Option<State>) {
+    fn state(&self) -> &State {
+        &self.0
+    }
+
+    fn weight(&self) -> &() {
+        &()
+    }
+
+    fn parent(&self) -> Option<&State> {
+        self.1.as_ref()
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum PredecessorMode {
+    /// Only the parent the state was processed from.
+    Single,
+    /// Every parent reaching the state with the same weight it was processed with.
+    All,
+}
+
+/// Records predecessors from the items of a search, which must be fed in the order the search
+/// yields them. The entries live in any [`Map`], e.g. an
+/// [`OptionSpace`](crate::set::OptionSpace) over a board for grid states.
+#[derive(Clone, Debug)]
+pub struct Predecessors<State, Weight, M = FastMap<State, PredecessorEntry<State, Weight>>> {
+    mode: PredecessorMode,
+    best: M,
+    phantom_data: PhantomData<(State, Weight)>,
+}
+
+/// What [`Predecessors`] keeps for a processed state. The parent it was processed from is kept
+/// apart from the other parents since an equal weight parent may also be recorded for the start,
+/// e.g. through a zero weight edge.
+#[derive(Clone, Debug)]
+pub struct PredecessorEntry<State, Weight> {
+    weight: Weight,
+    processed_parent: Option<State>,
+    parents: Vec<State>,
+}
+
+#[allow(dead_code)]
+impl<State, Weight> Predecessors<State, Weight>
+where
+    State: Eq + Hash + Clone,
+    Weight: Ord + Clone,
+{
+    pub fn new(mode: PredecessorMode) -> Result<Self, Error> {
+        Self::with_map(mode, FastMap::default())
+    }
+}
+
+#[allow(dead_code)]
+impl<State, Weight, M> Predecessors<State, Weight, M>
+where
+    State: Eq + Hash + Clone,
+    Weight: Ord + Clone,
+    M: Map<State, PredecessorEntry<State, Weight>>,
+{
+    /// `map` should start empty. [`PredecessorMode::All`] is rejected for a zero sized `Weight`
+    /// such as the `()` of [`Bfs`](crate::graph::Bfs) items, every parent would weigh the same.
+    pub fn with_map(mode: PredecessorMode, map: M) -> Result<Self, Error> {
+        if mode == PredecessorMode::All && size_of::<Weight>() == 0 {
+            return Err(Error::InvalidState(
+                "all predecessors need a weight to tell the shortest parents apart".into(),
+            ));
+        }
+        Ok(Self {
+            mode,
+            best: map,
+            phantom_data: PhantomData,
+        })
+    }
+
+    /// Entry of `state`, a state the map cannot hold was never recorded either.
+    fn entry(&self, state: &State) -> Option<&PredecessorEntry<State, Weight>> {
+        self.best.get(state).ok().flatten()
+    }
+
+    pub fn record<I: ParentItem<State, Weight>>(
+        &mut self,
+        item: &MaybeProcessed<I>,
+    ) -> Result<(), Error> {
+        match item {
+            Processed(item) => {
+                let entry = PredecessorEntry {
+                    weight: item.weight().clone(),
+                    processed_parent: item.parent().cloned(),
+                    parents: item.parent().cloned().into_iter().collect(),
+                };
+                if self.best.insert(item.state().clone(), entry)?.is_some() {
+                    return Err(Error::InvalidState("node is being processed twice".into()));
+                }
+            }
+            Skipped(item) => {
+                let entry = self
+                    .best
+                    .get(item.state())?
+                    .ok_or(Error::InvalidState("unprocessed node is skipped".into()))?;
+                if self.mode != PredecessorMode::All || entry.weight != *item.weight() {
+                    return Ok(());
+                }
+                if let Some(parent) = item.parent() {
+                    // `Map` has no `get_mut`, the entry is taken out and put back instead
+                    let mut entry = self.best.remove(item.state())?.unwrap();
+                    entry.parents.push(parent.clone());
+                    self.best.insert(item.state().clone(), entry)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn weight(&self, state: &State) -> Option<&Weight> {
+        self.entry(state).map(|entry| &entry.weight)
+    }
+
+    pub fn predecessors(&self, state: &State) -> &[State] {
+        self.entry(state)
+            .map(|entry| entry.parents.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Path from the start to `state` inclusive, following the parent each state was processed
+    /// from.
+    pub fn path_to(&self, state: &State) -> Option<Vec<State>> {
+        let mut entry = self.entry(state)?;
+        let mut path = vec![state.clone()];
+        while let Some(parent) = &entry.processed_parent {
+            path.push(parent.clone());
+            entry = self.entry(parent)?;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every state lying on any optimal path to the cheapest of `goals`, the goals included.
+    pub fn all_shortest_path_states(
+        &self,
+        goals: impl IntoIterator<Item = State>,
+    ) -> FastSet<State> {
+        let goals: Vec<_> = goals
+            .into_iter()
+            .filter_map(|goal| self.weight(&goal).cloned().map(|weight| (goal, weight)))
+            .collect();
+        let mut work: Vec<_> = match goals.iter().map(|(_, weight)| weight).min() {
+            None => return FastSet::default(),
+            Some(min_weight) => goals
+                .iter()
+                .filter(|(_, weight)| weight == min_weight)
+                .map(|(goal, _)| goal.clone())
+                .collect(),
+        };
+
+        let mut visited = FastSet::default();
+        while let Some(state) = work.pop() {
+            if !visited.insert(state.clone()) {
+                continue;
+            }
+            self.predecessors(&state)
+                .iter()
+                .filter(|&s| !visited.contains(s))
+                .for_each(|s| work.push(s.clone()));
+        }
+        visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::predecessor::{PredecessorMode, Predecessors};
+    use crate::graph::{Bfs, Dijkstra, NeighborFn, ZeroOneBfs};
+    use crate::hash::FastSet;
+    use crate::space::space2d::Board2d;
+    use crate::utils::cardinal;
+    use crate::utils::ures;
+    use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+    // a -> b -> d and a -> c -> d cost 2, a -> d directly costs 3, d -> e costs 1
+    struct Diamond;
+
+    const EDGES: [(char, char, ures); 6] = [
+        ('a', 'b', 1),
+        ('a', 'c', 1),
+        ('a', 'd', 3),
+        ('b', 'd', 1),
+        ('c', 'd', 1),
+        ('d', 'e', 1),
+    ];
+
+    impl NeighborFn<(char, ures, Option<char>)> for Diamond {
+        fn get_neighbors(
+            &mut self,
+            swm: &(char, ures, Option<char>),
+        ) -> impl IntoIterator<Item = (char, ures, Option<char>)> {
+            let (state, weight, _) = *swm;
+            EDGES
+                .iter()
+                .filter(move |(from, _, _)| *from == state)
+                .map(move |(_, to, cost)| (*to, weight + cost, Some(state)))
+        }
+    }
+
+    impl NeighborFn<(char, Option<char>)> for Diamond {
+        fn get_neighbors(
+            &mut self,
+            sm: &(char, Option<char>),
+        ) -> impl IntoIterator<Item = (char, Option<char>)> {
+            let (state, _) = *sm;
+            EDGES
+                .iter()
+                .filter(move |(from, _, _)| *from == state)
+                .map(move |(_, to, _)| (*to, Some(state)))
+        }
+    }
+
+    // a <-> b both ways for free, b -> c costs 1
+    struct ZeroLoop;
+
+    impl NeighborFn<(char, ures, Option<char>)> for ZeroLoop {
+        fn get_neighbors(
+            &mut self,
+            swm: &(char, ures, Option<char>),
+        ) -> impl IntoIterator<Item = (char, ures, Option<char>)> {
+            let (state, weight, _) = *swm;
+            [('a', 'b', 0), ('b', 'a', 0), ('b', 'c', 1)]
+                .into_iter()
+                .filter(move |(from, _, _)| *from == state)
+                .map(move |(_, to, cost)| (to, weight + cost, Some(state)))
+        }
+    }
+
+    #[test]
+    pub fn all_shortest_paths() -> Result<(), Error> {
+        let mut dijkstra = Dijkstra {
+            queue: BinaryHeap::new(),
+            neighbor_fn: Diamond,
+            visited: HashSet::new(),
+        };
+        dijkstra.push_queue(('a', 0, None));
+        let mut all = Predecessors::new(PredecessorMode::All)?;
+        for res in dijkstra {
+            all.record(&res?)?;
+        }
+
+        assert_eq!(all.weight(&'e'), Some(&3));
+        let mut parents = all.predecessors(&'d').to_vec();
+        parents.sort();
+        assert_eq!(parents, ['b', 'c']);
+        assert_eq!(all.path_to(&'e').unwrap().len(), 4);
+        assert_eq!(
+            all.all_shortest_path_states(['e', 'x']),
+            FastSet::from_iter(['a', 'b', 'c', 'd', 'e'])
+        );
+        assert_eq!(all.all_shortest_path_states(['b', 'c', 'e']).len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    pub fn single_bfs_path() -> Result<(), Error> {
+        let mut bfs = Bfs {
+            queue: VecDeque::new(),
+            neighbor_fn: Diamond,
+            visited: HashSet::new(),
+        };
+        bfs.queue.push_back(('a', None));
+        let mut single = Predecessors::new(PredecessorMode::Single)?;
+        for res in bfs {
+            single.record(&res?)?;
+        }
+
+        assert_eq!(single.path_to(&'e'), Some(vec!['a', 'd', 'e']));
+        assert_eq!(single.predecessors(&'d'), ['a']);
+        assert_eq!(single.path_to(&'x'), None);
+        assert!(Predecessors::<char, ()>::new(PredecessorMode::All).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn zero_weight_edge_back_to_start() -> Result<(), Error> {
+        let mut zero_one = ZeroOneBfs {
+            queue: VecDeque::new(),
+            neighbor_fn: ZeroLoop,
+            visited: HashSet::new(),
+        };
+        zero_one.queue.push_back(('a', 0, None));
+        let mut all = Predecessors::new(PredecessorMode::All)?;
+        for res in zero_one {
+            all.record(&res?)?;
+        }
+
+        assert_eq!(all.predecessors(&'a'), ['b']);
+        assert_eq!(all.path_to(&'a'), Some(vec!['a']));
+        assert_eq!(all.path_to(&'c'), Some(vec!['a', 'b', 'c']));
+        Ok(())
+    }
+
+    // open 3x3 grid, every step costs 1
+    struct Grid;
+
+    impl NeighborFn<([usize; 2], ures, Option<[usize; 2]>)> for Grid {
+        fn get_neighbors(
+            &mut self,
+            swm: &([usize; 2], ures, Option<[usize; 2]>),
+        ) -> impl IntoIterator<Item = ([usize; 2], ures, Option<[usize; 2]>)> {
+            let (pos, weight, _) = *swm;
+            cardinal(&pos)
+                .filter(|next| next.iter().all(|v| *v < 3))
+                .map(move |next| (next, weight + 1, Some(pos)))
+                .collect::<Vec<_>>()
+        }
+    }
+
+    #[test]
+    pub fn board_map() -> Result<(), Error> {
+        let mut dijkstra = Dijkstra {
+            queue: BinaryHeap::new(),
+            neighbor_fn: Grid,
+            visited: HashSet::new(),
+        };
+        dijkstra.push_queue(([0, 0], 0, None));
+        let board = Board2d::from(vec![vec![None; 3]; 3]);
+        let mut all = Predecessors::with_map(PredecessorMode::All, board)?;
+        for res in dijkstra {
+            all.record(&res?)?;
+        }
+
+        assert_eq!(all.weight(&[2, 2]), Some(&4));
+        assert_eq!(all.weight(&[3, 0]), None);
+        let mut parents = all.predecessors(&[1, 1]).to_vec();
+        parents.sort();
+        assert_eq!(parents, [[0, 1], [1, 0]]);
+        assert_eq!(all.path_to(&[2, 2]).unwrap().len(), 5);
+        assert_eq!(all.all_shortest_path_states([[2, 2]]).len(), 9);
+        Ok(())
+    }
+}
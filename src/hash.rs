@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+#[allow(dead_code)]
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The rustc "Fx" hasher: one rotate, xor and multiply per word. Fast on the small integer and
+/// array keys the solvers use, but trivially collidable so never use it on untrusted input.
+#[allow(dead_code)]
+#[derive(Default, Clone, Copy, Debug)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+#[allow(dead_code)]
+impl FxHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let mut remainder = chunks.remainder();
+        if remainder.len() >= 4 {
+            self.add_to_hash(u32::from_le_bytes(remainder[..4].try_into().unwrap()) as u64);
+            remainder = &remainder[4..];
+        }
+        if remainder.len() >= 2 {
+            self.add_to_hash(u16::from_le_bytes(remainder[..2].try_into().unwrap()) as u64);
+            remainder = &remainder[2..];
+        }
+        if let Some(byte) = remainder.first() {
+            self.add_to_hash(*byte as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+}
+
+#[allow(dead_code)]
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+/// Hasher of [`FastMap`] and [`FastSet`], [`FxHasher`] with the `fast-hash` feature and the std
+/// SipHash otherwise, so create the collections with `default()`.
+#[cfg(feature = "fast-hash")]
+pub type FastHashState = FxBuildHasher;
+#[cfg(not(feature = "fast-hash"))]
+pub type FastHashState = std::collections::hash_map::RandomState;
+
+pub type FastMap<K, V> = HashMap<K, V, FastHashState>;
+pub type FastSet<T> = HashSet<T, FastHashState>;
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::{FxBuildHasher, FxHasher};
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, Hasher};
+
+    #[test]
+    pub fn fx_hasher() {
+        let build = FxBuildHasher::default();
+        assert_eq!(build.hash_one([3usize, 4]), build.hash_one([3usize, 4]));
+        assert_ne!(build.hash_one([3usize, 4]), build.hash_one([4usize, 3]));
+
+        let mut bytes = FxHasher::default();
+        bytes.write(&7u64.to_le_bytes());
+        let mut word = FxHasher::default();
+        word.write_u64(7);
+        assert_eq!(bytes.finish(), word.finish());
+
+        let tails = (0..16)
+            .map(|len| {
+                let mut hasher = FxHasher::default();
+                hasher.write(&[1; 16][..len]);
+                hasher.finish()
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(tails.len(), 16);
+    }
+}
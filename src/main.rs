@@ -1,14 +1,19 @@
 use crate::error::Error;
-use crate::utils::{check_valid_question, get_input, submit, DisplayDebug};
+use crate::utils::{check_valid_question, get_input, set_dump_graph_dir, submit, DisplayDebug};
 use clap::{ArgAction, Parser};
 use dotenv::dotenv;
 use std::env;
+use std::path::PathBuf;
 use std::process::exit;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+mod cycle;
+mod disjoint_set;
 mod error;
 mod graph;
+mod hash;
 mod math;
+mod memo;
 mod nom;
 mod set;
 mod space;
@@ -47,6 +52,14 @@ struct Args {
     /// environment key for session cookie
     #[arg(short = 'c', long, default_value = "SESSION_COOKIE")]
     session_env: String,
+
+    /// directory to write graphviz dot files of solvers that dump their graphs
+    #[arg(long)]
+    dump_graph: Option<PathBuf>,
+
+    /// solve every selected part this many times and print timing statistics of the solver
+    #[arg(long)]
+    bench: Option<u32>,
 }
 
 fn main() {
@@ -54,6 +67,9 @@ fn main() {
     if args.dotenv {
         dotenv().expect("Failed to load `.env` file");
     }
+    if let Some(dir) = args.dump_graph {
+        set_dump_graph_dir(dir).expect("Failed to set graph dump directory");
+    }
     let session = env::var(&args.session_env)
         .unwrap_or_else(|_| panic!("Missing cookie, cannot find env {:?}", args.session_env));
     let (year, day, part) = (args.year, args.day, args.part);
@@ -83,7 +99,10 @@ fn main() {
         }
         let mut submit = args.submit;
         for part in part_range.clone() {
-            let res = solve_and_print_result(year, day, part, session.as_str());
+            let res = match args.bench {
+                Some(runs) => bench_and_print_result(year, day, part, session.as_str(), runs),
+                None => solve_and_print_result(year, day, part, session.as_str()),
+            };
             if res.is_err() {
                 error_code = 1;
                 if args.exit_on_failure {
@@ -175,6 +194,50 @@ fn solve_and_print_result(
     res
 }
 
+fn bench_and_print_result(
+    year: u16,
+    day: u8,
+    part: u8,
+    session: &str,
+    runs: u32,
+) -> Result<Box<dyn DisplayDebug>, Error> {
+    let input = get_input(year, day, session)?;
+    let mut timings = Vec::new();
+    let res = loop {
+        let start = Instant::now();
+        let res = utils::solve(year, day, part, input.as_str());
+        timings.push(start.elapsed());
+        if res.is_err() || timings.len() >= runs as usize {
+            break res;
+        }
+    };
+
+    timings.sort();
+    let mean = timings.iter().sum::<Duration>() / timings.len() as u32;
+    let hasher = if cfg!(feature = "fast-hash") {
+        "fx"
+    } else {
+        "sip"
+    };
+    println!(
+        "Bench on {} day {} part {} with {} hash over {} runs: min {}, median {}, mean {}",
+        year,
+        day,
+        part,
+        hasher,
+        timings.len(),
+        humantime::format_duration(timings[0]),
+        humantime::format_duration(timings[timings.len() / 2]),
+        humantime::format_duration(mean)
+    );
+    match &res {
+        Ok(res) => println!("Result on {} day {} part {}: {}", year, day, part, res),
+        Err(e) => println!("Error on {} day {} part {}: {}", year, day, part, e),
+    }
+
+    res
+}
+
 fn submit_result(
     year: u16,
     day: u8,
@@ -0,0 +1,396 @@
+use crate::error::Error;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+use std::str::FromStr;
+
+/// Arbitrary precision unsigned integer, little endian base 2^32 limbs without trailing zero
+/// limbs so equal values have equal limbs.
+#[derive(Eq, PartialEq, Hash, Clone, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+/// Largest power of ten fitting in a limb, used to convert from and to decimal.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+#[allow(dead_code)]
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u32)
+    }
+
+    fn normalize(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of significant bits, 0 for zero.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+        }
+    }
+
+    fn bit(&self, idx: u64) -> bool {
+        self.limbs
+            .get((idx / 32) as usize)
+            .is_some_and(|limb| limb >> (idx % 32) & 1 == 1)
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|val| u64::try_from(val).ok())
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |val, limb| val << 32 | *limb as u128),
+        )
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        let mut res = self.clone();
+        res.sub_assign_unchecked(other);
+        Some(res)
+    }
+
+    /// `self -= other`, `other` must not be larger.
+    fn sub_assign_unchecked(&mut self, other: &Self) {
+        let mut borrow = 0i64;
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let rhs = other.limbs.get(idx).copied().unwrap_or(0) as i64;
+            if rhs == 0 && borrow == 0 && idx >= other.limbs.len() {
+                break;
+            }
+            let diff = *limb as i64 - rhs - borrow;
+            borrow = (diff < 0) as i64;
+            *limb = diff.rem_euclid(1 << 32) as u32;
+        }
+        *self = std::mem::take(self).normalize();
+    }
+
+    fn shl1_or(&mut self, bit: bool) {
+        let mut carry = bit as u32;
+        for limb in self.limbs.iter_mut() {
+            let next_carry = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = next_carry;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    /// Quotient and remainder of the division by a single limb.
+    pub fn div_rem_u32(&self, divisor: u32) -> Result<(Self, u32), Error> {
+        if divisor == 0 {
+            return Err(Error::InvalidState("division by zero".into()));
+        }
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+        for (idx, limb) in self.limbs.iter().enumerate().rev() {
+            let cur = rem << 32 | *limb as u64;
+            quotient[idx] = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        Ok((Self { limbs: quotient }.normalize(), rem as u32))
+    }
+
+    /// Quotient and remainder, by shift and subtract.
+    pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), Error> {
+        match divisor.limbs[..] {
+            [] => return Err(Error::InvalidState("division by zero".into())),
+            [single] => {
+                let (quotient, rem) = self.div_rem_u32(single)?;
+                return Ok((quotient, Self::from(rem)));
+            }
+            _ => {}
+        }
+        if self < divisor {
+            return Ok((Self::zero(), self.clone()));
+        }
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut rem = Self::zero();
+        for idx in (0..self.bits()).rev() {
+            rem.shl1_or(self.bit(idx));
+            if rem >= *divisor {
+                rem.sub_assign_unchecked(divisor);
+                quotient[(idx / 32) as usize] |= 1 << (idx % 32);
+            }
+        }
+        Ok((Self { limbs: quotient }.normalize(), rem))
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut res = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = &res * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        res
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(impl From<$t> for BigUint {
+            fn from(val: $t) -> Self {
+                let mut val = val as u128;
+                let mut limbs = Vec::new();
+                while val > 0 {
+                    limbs.push(val as u32);
+                    val >>= 32;
+                }
+                Self { limbs }
+            }
+        })*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, usize, u128);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let rhs = other.limbs.get(idx).copied().unwrap_or(0) as u64;
+            if rhs == 0 && carry == 0 && idx >= other.limbs.len() {
+                break;
+            }
+            let sum = *limb as u64 + rhs + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, other: BigUint) {
+        *self += &other;
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut res = self.clone();
+        res += other;
+        res
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: BigUint) -> BigUint {
+        self += &other;
+        self
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, lhs) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, rhs) in other.limbs.iter().enumerate() {
+                let cur = limbs[i + j] as u64 + *lhs as u64 * *rhs as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, other: &BigUint) {
+        *self = &*self * other;
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |sum, val| sum + val)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |product, val| product * val)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::ParseError(
+                format!("Failed to parse unsigned integer: {:?}", s).into(),
+            ));
+        }
+        let head_len = match s.len() % DECIMAL_DIGITS {
+            0 => DECIMAL_DIGITS,
+            len => len,
+        };
+        let base = BigUint::from(DECIMAL_BASE);
+        let mut res = BigUint::zero();
+        let mut start = 0;
+        let mut end = head_len;
+        while start < s.len() {
+            // only ascii digits are left so the chunk always parses
+            let chunk = s[start..end].parse::<u32>().unwrap();
+            res = &res * &base + BigUint::from(chunk);
+            start = end;
+            end += DECIMAL_DIGITS;
+        }
+        Ok(res)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            // the divisor is a nonzero constant
+            let (quotient, chunk) = rest.div_rem_u32(DECIMAL_BASE).unwrap();
+            chunks.push(chunk);
+            rest = quotient;
+        }
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().copied().unwrap_or(0))?;
+        for chunk in chunks {
+            write!(f, "{:0width$}", chunk, width = DECIMAL_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::biguint::BigUint;
+
+    #[test]
+    pub fn arithmetic() -> Result<(), Error> {
+        let a = BigUint::from(u128::MAX);
+        let b: BigUint = "340282366920938463463374607431768211455".parse()?;
+        assert_eq!(a, b);
+        let sum = &a + &BigUint::from(1u32);
+        assert_eq!(sum.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(sum.to_u128(), None);
+        assert_eq!(sum.bits(), 129);
+
+        let product = &a * &a;
+        assert_eq!(
+            product.to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        let (quotient, rem) = (&product + &BigUint::from(12345u32)).div_rem(&a)?;
+        assert_eq!((quotient, rem), (a.clone(), BigUint::from(12345u32)));
+        assert_eq!(product.checked_sub(&product), Some(BigUint::zero()));
+        assert_eq!(a.checked_sub(&product), None);
+        assert!(a.div_rem(&BigUint::zero()).is_err());
+
+        for (x, y) in [
+            (0u128, 7u128),
+            (99, 100),
+            (1 << 70, 3 << 33),
+            (u64::MAX as u128, 1),
+        ] {
+            let (quotient, rem) = BigUint::from(x).div_rem(&BigUint::from(y))?;
+            assert_eq!(
+                (quotient.to_u128(), rem.to_u128()),
+                (Some(x / y), Some(x % y))
+            );
+            assert_eq!(BigUint::from(x).to_string(), x.to_string());
+        }
+        assert_eq!(BigUint::from(2u32).pow(100).bits(), 101);
+        assert_eq!(
+            (1..=30u32)
+                .map(BigUint::from)
+                .product::<BigUint>()
+                .to_string(),
+            "265252859812191058636308480000000"
+        );
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert!("12a".parse::<BigUint>().is_err());
+        assert_eq!(
+            "000000000000000000042".parse::<BigUint>()?.to_u64(),
+            Some(42)
+        );
+        Ok(())
+    }
+}
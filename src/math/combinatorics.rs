@@ -0,0 +1,301 @@
+use crate::error::Error;
+use crate::math::{is_prime, mod_inv, mod_mul};
+use crate::utils::{musize, ures};
+
+/// Factorials and inverse factorials modulo a prime, answering binomials in constant time.
+#[derive(Debug, Clone)]
+pub struct FactorialTable {
+    modulus: ures,
+    fact: Vec<ures>,
+    inv_fact: Vec<ures>,
+}
+
+#[allow(dead_code)]
+impl FactorialTable {
+    /// Tables for `0..=n`, `modulus` must be a prime above `n` so every entry is invertible.
+    pub fn new(n: musize, modulus: ures) -> Result<Self, Error> {
+        if !is_prime(modulus)? || modulus as musize <= n {
+            return Err(Error::InvalidState(
+                format!("modulus must be a prime above {}, got {}", n, modulus).into(),
+            ));
+        }
+        let mut fact = vec![1; n + 1];
+        for i in 1..=n {
+            fact[i] = mod_mul(fact[i - 1], i as ures, modulus)?;
+        }
+        let mut inv_fact = vec![1; n + 1];
+        inv_fact[n] = mod_inv(fact[n], modulus)?;
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = mod_mul(inv_fact[i], i as ures, modulus)?;
+        }
+        Ok(Self {
+            modulus,
+            fact,
+            inv_fact,
+        })
+    }
+
+    /// Tables for every residue, as [`FactorialTable::choose_lucas`] needs.
+    pub fn full(modulus: ures) -> Result<Self, Error> {
+        Self::new((modulus as musize).saturating_sub(1), modulus)
+    }
+
+    pub fn modulus(&self) -> ures {
+        self.modulus
+    }
+
+    /// Largest `n` the tables hold.
+    pub fn max(&self) -> musize {
+        self.fact.len() - 1
+    }
+
+    fn lookup(table: &[ures], n: musize) -> Result<ures, Error> {
+        table.get(n).copied().ok_or_else(|| {
+            Error::InvalidState(format!("out of bound, {} is past the table", n).into())
+        })
+    }
+
+    pub fn factorial(&self, n: musize) -> Result<ures, Error> {
+        Self::lookup(&self.fact, n)
+    }
+
+    pub fn inv_factorial(&self, n: musize) -> Result<ures, Error> {
+        Self::lookup(&self.inv_fact, n)
+    }
+
+    pub fn choose(&self, m: musize, n: musize) -> Result<ures, Error> {
+        if n > m {
+            return Err(Error::InvalidState(
+                format!("invalid argument of [{} choose {}]", m, n).into(),
+            ));
+        }
+        let res = mod_mul(self.factorial(m)?, self.inv_factorial(n)?, self.modulus)?;
+        mod_mul(res, self.inv_factorial(m - n)?, self.modulus)
+    }
+
+    /// Ordered selections of `n` out of `m`, `m! / (m - n)!`.
+    pub fn permutations(&self, m: musize, n: musize) -> Result<ures, Error> {
+        if n > m {
+            return Err(Error::InvalidState(
+                format!("invalid argument of [{} permute {}]", m, n).into(),
+            ));
+        }
+        mod_mul(self.factorial(m)?, self.inv_factorial(m - n)?, self.modulus)
+    }
+
+    /// `m choose n` modulo the prime for any `m` by Lucas' theorem, multiplying the binomials
+    /// of the base `modulus` digits. Needs the [`FactorialTable::full`] table.
+    pub fn choose_lucas(&self, mut m: ures, mut n: ures) -> Result<ures, Error> {
+        if n > m {
+            return Err(Error::InvalidState(
+                format!("invalid argument of [{} choose {}]", m, n).into(),
+            ));
+        }
+        if self.max() + 1 != self.modulus as musize {
+            return Err(Error::InvalidState(
+                "lucas needs the table up to modulus - 1".into(),
+            ));
+        }
+        let mut res = 1 % self.modulus;
+        while n > 0 {
+            let (m_digit, n_digit) = (m % self.modulus, n % self.modulus);
+            if n_digit > m_digit {
+                return Ok(0);
+            }
+            let digit_choose = self.choose(m_digit as musize, n_digit as musize)?;
+            res = mod_mul(res, digit_choose, self.modulus)?;
+            m /= self.modulus;
+            n /= self.modulus;
+        }
+        Ok(res)
+    }
+}
+
+/// Every ordering of `items`, in lexicographic order of their positions.
+#[allow(dead_code)]
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        indices: Some((0..items.len()).collect()),
+    }
+}
+
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Permutations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let res = indices.iter().map(|i| self.items[*i].clone()).collect();
+        // next lexicographic permutation of the indices, none after the descending one
+        match (1..indices.len())
+            .rev()
+            .find(|i| indices[i - 1] < indices[*i])
+        {
+            None => self.indices = None,
+            Some(i) => {
+                let pivot = i - 1;
+                let swap = (i..indices.len())
+                    .rev()
+                    .find(|j| indices[*j] > indices[pivot])
+                    .unwrap();
+                indices.swap(pivot, swap);
+                indices[i..].reverse();
+            }
+        }
+        Some(res)
+    }
+}
+
+/// Every `k` element subset of `items` keeping their order, in lexicographic order of their
+/// positions.
+#[allow(dead_code)]
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (k <= items.len()).then(|| (0..k).collect()),
+    }
+}
+
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let res = indices.iter().map(|i| self.items[*i].clone()).collect();
+        let (len, k) = (self.items.len(), indices.len());
+        match (0..k).rev().find(|i| indices[*i] != i + len - k) {
+            None => self.indices = None,
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+        }
+        Some(res)
+    }
+}
+
+/// Every way of picking one element from each slice, the last slice changing fastest.
+#[allow(dead_code)]
+pub fn cartesian_product<'a, T: Clone>(
+    slices: impl IntoIterator<Item = &'a [T]>,
+) -> CartesianProduct<'a, T> {
+    let slices = slices.into_iter().collect::<Vec<_>>();
+    let indices = (!slices.iter().any(|s| s.is_empty())).then(|| vec![0; slices.len()]);
+    CartesianProduct { slices, indices }
+}
+
+/// [`cartesian_product`] of `k` copies of `items`.
+#[allow(dead_code)]
+pub fn cartesian_power<T: Clone>(items: &[T], k: usize) -> CartesianProduct<'_, T> {
+    cartesian_product(std::iter::repeat_n(items, k))
+}
+
+pub struct CartesianProduct<'a, T> {
+    slices: Vec<&'a [T]>,
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for CartesianProduct<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let res = indices
+            .iter()
+            .zip(self.slices.iter())
+            .map(|(i, s)| s[*i].clone())
+            .collect();
+        let carried = indices
+            .iter_mut()
+            .zip(self.slices.iter())
+            .rev()
+            .all(|(i, s)| {
+                *i += 1;
+                if *i == s.len() {
+                    *i = 0;
+                    true
+                } else {
+                    false
+                }
+            });
+        if carried {
+            self.indices = None;
+        }
+        Some(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::biguint::BigUint;
+    use crate::math::choose_big;
+    use crate::math::combinatorics::{
+        cartesian_power, cartesian_product, combinations, permutations, FactorialTable,
+    };
+
+    #[test]
+    pub fn factorial_table() -> Result<(), Error> {
+        let p = 1_000_000_007;
+        let table = FactorialTable::new(1000, p)?;
+        assert_eq!(table.factorial(20)?, 146326063);
+        for (m, n) in [(10, 3), (1000, 500), (57, 0), (57, 57)] {
+            let exact = choose_big(m, n)?;
+            let (_, rem) = exact.div_rem(&BigUint::from(p))?;
+            assert_eq!(BigUint::from(table.choose(m, n)?), rem);
+        }
+        assert_eq!(table.permutations(5, 2)?, 20);
+        assert!(matches!(table.choose(3, 4), Err(Error::InvalidState(_))));
+        assert!(table.choose(1001, 1).is_err());
+        assert!(FactorialTable::new(10, 12).is_err());
+        assert!(FactorialTable::new(13, 13).is_err());
+
+        let lucas = FactorialTable::full(13)?;
+        for (m, n) in [(1000, 17), (1000, 500), (12345, 678), (169, 13)] {
+            let exact = choose_big(m, n)?;
+            let (_, rem) = exact.div_rem(&BigUint::from(13u32))?;
+            assert_eq!(BigUint::from(lucas.choose_lucas(m as _, n as _)?), rem);
+        }
+        assert!(table.choose_lucas(10, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn iterators() {
+        let perms = permutations(&['a', 'b', 'c']).collect::<Vec<_>>();
+        assert_eq!(perms.len(), 6);
+        assert_eq!(perms[0], ['a', 'b', 'c']);
+        assert_eq!(perms[1], ['a', 'c', 'b']);
+        assert_eq!(perms[5], ['c', 'b', 'a']);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+
+        let combs = combinations(&[1, 2, 3, 4], 2).collect::<Vec<_>>();
+        assert_eq!(
+            combs,
+            [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]].map(Vec::from)
+        );
+        assert_eq!(combinations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+
+        let product = cartesian_product([&[1, 2][..], &[3][..], &[4, 5][..]]).collect::<Vec<_>>();
+        assert_eq!(
+            product,
+            [[1, 3, 4], [1, 3, 5], [2, 3, 4], [2, 3, 5]].map(Vec::from)
+        );
+        assert_eq!(cartesian_product([&[1][..], &[][..]]).count(), 0);
+        assert_eq!(cartesian_power(&['+', '*', '|'], 4).count(), 81);
+        assert_eq!(cartesian_power(&['+'], 0).count(), 1);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::error::Error;
+use crate::math::rational::Rational;
+use crate::math::MathInt;
+use std::ops::{Index, IndexMut};
+use thiserror::Error;
+
+/// Why [`Matrix::solve`] found no single solution of `A x = b`.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SolveError {
+    #[error("no solution")]
+    Inconsistent,
+    #[error("infinite solutions, rank {rank} for {unknowns} unknowns")]
+    Underdetermined { rank: usize, unknowns: usize },
+    #[error("solution {0:?} is not integer")]
+    NotInteger(Vec<Rational>),
+    #[error(transparent)]
+    Error(#[from] Error),
+}
+
+impl From<SolveError> for Error {
+    fn from(value: SolveError) -> Self {
+        match value {
+            SolveError::Error(e) => e,
+            e => Error::Unsolvable(e.to_string().into()),
+        }
+    }
+}
+
+/// Dense row-major matrix.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> Matrix<T> {
+    pub fn from_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, Error> {
+        let mut data = Vec::new();
+        let mut row_count = 0;
+        let mut cols = None;
+        for row in rows {
+            let len = data.len();
+            data.extend(row);
+            let row_len = data.len() - len;
+            if *cols.get_or_insert(row_len) != row_len {
+                return Err(Error::InvalidState("matrix rows differ in length".into()));
+            }
+            row_count += 1;
+        }
+        Ok(Self {
+            rows: row_count,
+            cols: cols.unwrap_or(0),
+            data,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.rows && col < self.cols).then(|| &self.data[row * self.cols + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for col in 0..self.cols {
+                self.data.swap(a * self.cols + col, b * self.cols + col);
+            }
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("matrix index out of bound")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "matrix index out of bound"
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// Result of [`Matrix::eliminate`]: column of the pivot of each nonzero row, and the
+/// determinant of the row operations applied.
+struct Elimination {
+    pivots: Vec<usize>,
+    det_factor: Rational,
+}
+
+#[allow(dead_code)]
+impl Matrix<Rational> {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![Rational::ZERO; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::zero(n, n);
+        for i in 0..n {
+            res[(i, i)] = Rational::ONE;
+        }
+        res
+    }
+
+    pub fn from_int_rows<T: MathInt, R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, Error> {
+        Self::from_rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Rational::from_int)),
+        )
+    }
+
+    /// Gauss-Jordan elimination into reduced row echelon form, pivoting on the first `cols`
+    /// columns only.
+    fn eliminate(&mut self, cols: usize) -> Result<Elimination, Error> {
+        let mut pivots = Vec::new();
+        let mut det_factor = Rational::ONE;
+        for col in 0..cols {
+            let row = pivots.len();
+            let Some(pivot_row) = (row..self.rows).find(|r| !self[(*r, col)].is_zero()) else {
+                continue;
+            };
+            if pivot_row != row {
+                self.swap_rows(pivot_row, row);
+                det_factor = det_factor.checked_neg()?;
+            }
+            let pivot = self[(row, col)];
+            det_factor = det_factor.checked_mul(&pivot)?;
+            for c in col..self.cols {
+                self[(row, c)] = self[(row, c)].checked_div(&pivot)?;
+            }
+            for other in (0..self.rows).filter(|r| *r != row) {
+                let factor = self[(other, col)];
+                if factor.is_zero() {
+                    continue;
+                }
+                for c in col..self.cols {
+                    let val = self[(row, c)].checked_mul(&factor)?;
+                    self[(other, c)] = self[(other, c)].checked_sub(&val)?;
+                }
+            }
+            pivots.push(col);
+        }
+        Ok(Elimination { pivots, det_factor })
+    }
+
+    pub fn reduced_row_echelon(&self) -> Result<Self, Error> {
+        let mut res = self.clone();
+        res.eliminate(self.cols)?;
+        Ok(res)
+    }
+
+    pub fn rank(&self) -> Result<usize, Error> {
+        Ok(self.clone().eliminate(self.cols)?.pivots.len())
+    }
+
+    fn check_square(&self) -> Result<(), Error> {
+        if self.rows != self.cols {
+            return Err(Error::InvalidState(
+                format!("matrix is {}x{}, not square", self.rows, self.cols).into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn det(&self) -> Result<Rational, Error> {
+        self.check_square()?;
+        let elimination = self.clone().eliminate(self.cols)?;
+        if elimination.pivots.len() < self.rows {
+            Ok(Rational::ZERO)
+        } else {
+            Ok(elimination.det_factor)
+        }
+    }
+
+    /// [`Error::Unsolvable`] when the matrix is singular.
+    pub fn inverse(&self) -> Result<Self, Error> {
+        self.check_square()?;
+        let n = self.rows;
+        let mut augmented = Self::zero(n, 2 * n);
+        for row in 0..n {
+            for col in 0..n {
+                augmented[(row, col)] = self[(row, col)];
+            }
+            augmented[(row, n + row)] = Rational::ONE;
+        }
+        if augmented.eliminate(n)?.pivots.len() < n {
+            return Err(Error::Unsolvable("matrix is singular".into()));
+        }
+        Self::from_rows((0..n).map(|row| augmented.row(row)[n..].to_vec()))
+    }
+
+    /// The single solution of `self * x = b`.
+    pub fn solve(&self, b: &[Rational]) -> Result<Vec<Rational>, SolveError> {
+        if b.len() != self.rows {
+            return Err(Error::InvalidState(
+                format!("{} values for {} equations", b.len(), self.rows).into(),
+            )
+            .into());
+        }
+        let mut augmented = Self::zero(self.rows, self.cols + 1);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                augmented[(row, col)] = self[(row, col)];
+            }
+            augmented[(row, self.cols)] = b[row];
+        }
+        let pivots = augmented.eliminate(self.cols)?.pivots;
+        if (pivots.len()..self.rows).any(|row| !augmented[(row, self.cols)].is_zero()) {
+            return Err(SolveError::Inconsistent);
+        }
+        if pivots.len() < self.cols {
+            return Err(SolveError::Underdetermined {
+                rank: pivots.len(),
+                unknowns: self.cols,
+            });
+        }
+        Ok((0..self.cols)
+            .map(|row| augmented[(row, self.cols)])
+            .collect())
+    }
+
+    /// [`Matrix::solve`] requiring every unknown to be an integer.
+    pub fn solve_integer<T: MathInt>(&self, b: &[T]) -> Result<Vec<T>, SolveError> {
+        let b = b
+            .iter()
+            .copied()
+            .map(Rational::from_int)
+            .collect::<Vec<_>>();
+        let solution = self.solve(&b)?;
+        if !solution.iter().all(Rational::is_integer) {
+            return Err(SolveError::NotInteger(solution));
+        }
+        Ok(solution
+            .iter()
+            .map(|val| val.to_integer())
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::matrix::{Matrix, SolveError};
+    use crate::math::rational::Rational;
+    use crate::utils::ires;
+
+    #[test]
+    pub fn elimination() -> Result<(), Error> {
+        let m = Matrix::from_int_rows([[2, 1, 1], [1, 3, 2], [1, 0, 0]] as [[ires; 3]; 3])?;
+        assert_eq!(m.rank()?, 3);
+        assert_eq!(m.det()?, Rational::from(-1));
+        let inverse = m.inverse()?;
+        let expected =
+            Matrix::from_int_rows([[0, 0, 1], [-2, 1, 3], [3, -1, -5]] as [[ires; 3]; 3])?;
+        assert_eq!(inverse, expected);
+
+        let singular = Matrix::from_int_rows([[1, 2], [2, 4]] as [[ires; 2]; 2])?;
+        assert_eq!(singular.rank()?, 1);
+        assert_eq!(singular.det()?, Rational::ZERO);
+        assert!(matches!(singular.inverse(), Err(Error::Unsolvable(_))));
+        assert!(Matrix::from_int_rows([vec![1 as ires], vec![1, 2]]).is_err());
+        assert!(Matrix::from_int_rows([[1 as ires, 2]])?.det().is_err());
+        assert_eq!(
+            Matrix::from_int_rows([[0 as ires, 2, 4], [0, 1, 3]])?.reduced_row_echelon()?,
+            Matrix::from_int_rows([[0 as ires, 1, 0], [0, 0, 1]])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn solve() -> Result<(), Error> {
+        let m = Matrix::from_int_rows([[94, 22], [34, 67]] as [[ires; 2]; 2])?;
+        assert_eq!(m.solve_integer::<ires>(&[8400, 5400]), Ok(vec![80, 40]));
+        assert!(matches!(
+            m.solve_integer::<ires>(&[8401, 5400]),
+            Err(SolveError::NotInteger(_))
+        ));
+
+        let dependent = Matrix::from_int_rows([[1, 2], [2, 4]] as [[ires; 2]; 2])?;
+        assert_eq!(
+            dependent.solve_integer::<ires>(&[3, 6]),
+            Err(SolveError::Underdetermined {
+                rank: 1,
+                unknowns: 2
+            })
+        );
+        assert_eq!(
+            dependent.solve_integer::<ires>(&[3, 7]),
+            Err(SolveError::Inconsistent)
+        );
+
+        let tall = Matrix::from_int_rows([[1, 0], [0, 1], [1, 1]] as [[ires; 2]; 3])?;
+        assert_eq!(tall.solve_integer::<ires>(&[2, 3, 5]), Ok(vec![2, 3]));
+        assert!(matches!(
+            Error::from(tall.solve_integer::<ires>(&[2, 3, 6]).unwrap_err()),
+            Error::Unsolvable(_)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+use crate::error::Error;
+use crate::math::biguint::BigUint;
+use crate::utils::{musize, ures};
+use array_init::from_iter;
+use num_integer::Integer;
+use std::fmt::{Debug, Display};
+use std::iter;
+use std::sync::LazyLock;
+
+pub mod biguint;
+pub mod combinatorics;
+pub mod matrix;
+pub mod poly;
+pub mod rational;
+
+pub fn factorial(n: musize) -> Option<ures> {
+    static CACHE: LazyLock<[ures; 21]> = LazyLock::new(|| {
+        from_iter(
+            iter::once(1)
+                .chain(Factorial {
+                    current: 1,
+                    current_fac: 1,
+                })
+                .take(21),
+        )
+        .unwrap()
+    });
+
+    CACHE.get(n).copied()
+}
+
+#[allow(dead_code)]
+pub fn choose(m: musize, n: musize) -> Result<Option<ures>, Error> {
+    match n {
+        n if n > m => Err(Error::InvalidState(
+            format!("invalid argument of [{} choose {}]", m, n).into(),
+        )),
+        0 => Ok(Some(1)),
+        1 => Ok(Some(m as ures)),
+        n if n > (m - n) => choose(m, m - n),
+        n => Ok(choose_inner(m, n)),
+    }
+}
+
+fn choose_inner(m: musize, n: musize) -> Option<ures> {
+    match n {
+        0 => Some(1),
+        1 => Some(m as ures),
+        n if n > (m - n) => choose_inner(m, m - n),
+        n => {
+            let mut val1: ures = 1;
+            let mut val2: ures = if n <= 20 {
+                factorial(n)?
+            } else {
+                factorial(20)?
+            };
+            let m = m as ures;
+            let n = n as ures;
+            for i in 1..=n {
+                let mut mul1 = m - n + i;
+                let gcd = val2.gcd(&mul1);
+                val2 /= gcd;
+                mul1 /= gcd;
+
+                if i > 20 {
+                    let mut mul2 = i;
+                    let gcd = val1.gcd(&mul2);
+                    val1 /= gcd;
+                    mul2 /= gcd;
+
+                    let gcd = mul1.gcd(&mul2);
+                    mul1 /= gcd;
+                    mul2 /= gcd;
+
+                    val1 = val1.checked_mul(mul1)?;
+                    val2 = val2.checked_mul(mul2)?;
+                } else {
+                    val1 = val1.checked_mul(mul1)?;
+                }
+            }
+
+            Some(val1)
+        }
+    }
+}
+
+/// Exact `n!`, for when [`factorial`] runs out of range.
+#[allow(dead_code)]
+pub fn factorial_big(n: musize) -> BigUint {
+    (2..=n).map(BigUint::from).product()
+}
+
+/// Exact `m choose n`, for when [`choose`] overflows.
+#[allow(dead_code)]
+pub fn choose_big(m: musize, n: musize) -> Result<BigUint, Error> {
+    if n > m {
+        return Err(Error::InvalidState(
+            format!("invalid argument of [{} choose {}]", m, n).into(),
+        ));
+    }
+    let n = n.min(m - n);
+    // every prefix product of the falling factorial is divisible by the matching factorial
+    (1..=n).try_fold(BigUint::one(), |res, i| {
+        let (quotient, _) = (res * BigUint::from(m - n + i)).div_rem(&BigUint::from(i))?;
+        Ok(quotient)
+    })
+}
+
+struct Factorial {
+    current: ures,
+    current_fac: ures,
+}
+
+impl Iterator for Factorial {
+    type Item = ures;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current_fac = self.current_fac.checked_mul(self.current)?;
+        self.current = self.current.checked_add(1)?;
+        Some(self.current_fac)
+    }
+}
+
+/// Primitive integers the number theory helpers accept, mainly [`ures`] and
+/// [`ires`](crate::utils::ires). Work is done in `i128`/`u128` and converted back, failing when
+/// the result does not fit.
+#[allow(dead_code)]
+pub trait MathInt: Copy + Ord + Debug + Display {
+    fn to_i128(self) -> i128;
+    fn from_i128(val: i128) -> Result<Self, Error>;
+}
+
+macro_rules! impl_math_int {
+    ($($t:ty),*) => {
+        $(impl MathInt for $t {
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(val: i128) -> Result<Self, Error> {
+                Self::try_from(val).map_err(|_| {
+                    Error::InvalidState(
+                        format!("overflow, {} does not fit in {}", val, stringify!($t)).into(),
+                    )
+                })
+            }
+        })*
+    };
+}
+
+impl_math_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+fn positive_modulus<T: MathInt>(m: T) -> Result<i128, Error> {
+    match m.to_i128() {
+        m if m > 0 => Ok(m),
+        m => Err(Error::InvalidState(
+            format!("modulus must be positive, got {}", m).into(),
+        )),
+    }
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`. Fails for unsigned `T` when a
+/// coefficient is negative, use a signed type then.
+#[allow(dead_code)]
+pub fn ext_gcd<T: MathInt>(a: T, b: T) -> Result<(T, T, T), Error> {
+    let (g, x, y) = ext_gcd_i128(a.to_i128(), b.to_i128())?;
+    Ok((T::from_i128(g)?, T::from_i128(x)?, T::from_i128(y)?))
+}
+
+fn ext_gcd_i128(a: i128, b: i128) -> Result<(i128, i128, i128), Error> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0);
+    let (mut old_y, mut y) = (0i128, 1);
+    // only the coefficients of the last step can leave i128, they are discarded and the kept
+    // ones fit so wrapping still leaves them exact
+    while r != 0 {
+        let q = old_r.wrapping_div(r);
+        (old_r, r) = (r, old_r.wrapping_rem(r));
+        (old_x, x) = (x, old_x.wrapping_sub(q.wrapping_mul(x)));
+        (old_y, y) = (y, old_y.wrapping_sub(q.wrapping_mul(y)));
+    }
+    if old_r >= 0 {
+        return Ok((old_r, old_x, old_y));
+    }
+    match (
+        old_r.checked_neg(),
+        old_x.checked_neg(),
+        old_y.checked_neg(),
+    ) {
+        (Some(g), Some(x), Some(y)) => Ok((g, x, y)),
+        _ => Err(Error::InvalidState(
+            format!("overflow, gcd of {} and {} does not fit in i128", a, b).into(),
+        )),
+    }
+}
+
+/// `a * b mod m` in `0..m`, never overflows even when the product does not fit in `u128`.
+#[allow(dead_code)]
+pub fn mod_mul<T: MathInt>(a: T, b: T, m: T) -> Result<T, Error> {
+    let m = positive_modulus(m)?;
+    T::from_i128(mod_mul_i128(a.to_i128(), b.to_i128(), m))
+}
+
+fn mod_mul_i128(a: i128, b: i128, m: i128) -> i128 {
+    let m = m as u128;
+    let (mut a, mut b) = (
+        a.rem_euclid(m as i128) as u128,
+        b.rem_euclid(m as i128) as u128,
+    );
+    if let Some(product) = a.checked_mul(b) {
+        return (product % m) as i128;
+    }
+    // double and add, every operand is below m < 2^127 so the sums fit in u128
+    let mut res = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            res = (res + a) % m;
+        }
+        a = (a + a) % m;
+        b >>= 1;
+    }
+    res as i128
+}
+
+/// `base^exp mod m` in `0..m`, a negative `exp` raises the inverse of `base`.
+#[allow(dead_code)]
+pub fn mod_pow<T: MathInt>(base: T, exp: T, m: T) -> Result<T, Error> {
+    let modulus = positive_modulus(m)?;
+    let exp = exp.to_i128();
+    let mut base = if exp < 0 {
+        mod_inv(base, m)?.to_i128()
+    } else {
+        base.to_i128().rem_euclid(modulus)
+    };
+    let mut exp = exp.unsigned_abs();
+    let mut res = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = mod_mul_i128(res, base, modulus);
+        }
+        base = mod_mul_i128(base, base, modulus);
+        exp >>= 1;
+    }
+    T::from_i128(res)
+}
+
+/// Inverse of `a` modulo `m` in `0..m`, [`Error::Unsolvable`] when they are not coprime.
+#[allow(dead_code)]
+pub fn mod_inv<T: MathInt>(a: T, m: T) -> Result<T, Error> {
+    let modulus = positive_modulus(m)?;
+    let (g, x, _) = ext_gcd_i128(a.to_i128().rem_euclid(modulus), modulus)?;
+    if g != 1 {
+        return Err(Error::Unsolvable(
+            format!("{} has no inverse modulo {}", a, m).into(),
+        ));
+    }
+    T::from_i128(x.rem_euclid(modulus))
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs whose moduli need not be coprime.
+/// Returns `(x, l)` where `l` is the lcm of the moduli and every solution is `x` modulo `l`,
+/// [`Error::Unsolvable`] when the congruences contradict each other.
+#[allow(dead_code)]
+pub fn crt<T: MathInt>(congruences: impl IntoIterator<Item = (T, T)>) -> Result<(T, T), Error> {
+    let (mut x, mut l) = (0i128, 1i128);
+    for (residue, m) in congruences {
+        let m = positive_modulus(m)?;
+        let residue = residue.to_i128().rem_euclid(m);
+        let (g, p, _) = ext_gcd_i128(l, m)?;
+        let diff = residue - x;
+        if diff % g != 0 {
+            return Err(Error::Unsolvable(
+                format!("x = {} mod {} contradicts x = {} mod {}", residue, m, x, l).into(),
+            ));
+        }
+        let step = m / g;
+        let next_l = l
+            .checked_mul(step)
+            .ok_or_else(|| Error::InvalidState("overflow, lcm of moduli does not fit".into()))?;
+        // x + l * k where k = diff / g * p (mod m / g)
+        let k = mod_mul_i128(diff / g, p, step);
+        // both terms are below next_l so their sum fits in u128
+        x = ((x as u128 + mod_mul_i128(l, k, next_l) as u128) % next_l as u128) as i128;
+        l = next_l;
+    }
+    Ok((T::from_i128(x)?, T::from_i128(l)?))
+}
+
+/// Deterministic Miller-Rabin, exact for every 64 bit number and fails above that since the
+/// witness set is no longer known to be enough.
+#[allow(dead_code)]
+pub fn is_prime<T: MathInt>(n: T) -> Result<bool, Error> {
+    let n = n.to_i128();
+    if n < 2 {
+        return Ok(false);
+    }
+    if n > u64::MAX as i128 {
+        return Err(Error::InvalidState(
+            format!("primality of {} is only checked up to 64 bits", n).into(),
+        ));
+    }
+    const BASES: [i128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if let Some(base) = BASES.iter().find(|base| n % **base == 0) {
+        return Ok(n == *base);
+    }
+    let twos = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> twos;
+    for base in BASES {
+        let mut x = mod_pow(base, odd, n)?;
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        let witness = !(1..twos).any(|_| {
+            x = mod_mul_i128(x, x, n);
+            x == n - 1
+        });
+        if witness {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Least common multiple of the absolute values, 1 when empty and 0 when any is 0.
+#[allow(dead_code)]
+pub fn lcm_all<T: MathInt>(vals: impl IntoIterator<Item = T>) -> Result<T, Error> {
+    let res = vals.into_iter().try_fold(1i128, |l, val| {
+        let val = val.to_i128().checked_abs().ok_or_else(|| {
+            Error::InvalidState(format!("overflow, |{}| does not fit in i128", val).into())
+        })?;
+        if val == 0 || l == 0 {
+            return Ok(0);
+        }
+        (l / l.gcd(&val)).checked_mul(val).ok_or_else(|| {
+            Error::InvalidState(format!("overflow, lcm of {} and {}", l, val).into())
+        })
+    })?;
+    T::from_i128(res)
+}
+
+/// Floor of the square root, fails on negative numbers.
+#[allow(dead_code)]
+pub fn isqrt<T: MathInt>(n: T) -> Result<T, Error> {
+    let n = n.to_i128();
+    if n < 0 {
+        return Err(Error::InvalidState(
+            format!("square root of negative number {}", n).into(),
+        ));
+    }
+    let n = n as u128;
+    let mut root = (n as f64).sqrt() as u128;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    T::from_i128(root as i128)
+}
+
+/// Floor of the cube root, so rounded toward negative infinity for negative numbers.
+#[allow(dead_code)]
+pub fn icbrt<T: MathInt>(n: T) -> Result<T, Error> {
+    let n = n.to_i128();
+    let root = icbrt_u128(n.unsigned_abs());
+    // the magnitude of the root is below 2^43 so it fits in i128
+    match (n >= 0, root.pow(3) == n.unsigned_abs()) {
+        (true, _) => T::from_i128(root as i128),
+        (false, true) => T::from_i128(-(root as i128)),
+        (false, false) => T::from_i128(-(root as i128) - 1),
+    }
+}
+
+/// Floor of the cube root, a cube that does not fit in `u128` is larger than any `n`.
+fn icbrt_u128(n: u128) -> u128 {
+    let cube = |root: u128| root.checked_mul(root).and_then(|sq| sq.checked_mul(root));
+    let mut root = (n as f64).cbrt() as u128;
+    while cube(root).is_none_or(|c| c > n) {
+        root -= 1;
+    }
+    while cube(root + 1).is_some_and(|c| c <= n) {
+        root += 1;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::{
+        choose, choose_big, crt, ext_gcd, factorial, factorial_big, icbrt, is_prime, isqrt,
+        lcm_all, mod_inv, mod_mul, mod_pow,
+    };
+    use crate::utils::{ires, ures};
+
+    #[test]
+    pub fn modular() -> Result<(), Error> {
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (12, -18), (17, 0)] {
+            let (g, x, y): (ires, ires, ires) = ext_gcd(a, b)?;
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(g as u64, num_integer::gcd(a, b).unsigned_abs() as u64);
+        }
+        assert!(ext_gcd::<ures>(3, 7).is_err());
+
+        assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 2, u64::MAX)?, 2);
+        assert_eq!(mod_mul::<ires>(-3, 4, 5)?, 3);
+        assert_eq!(mod_pow::<ures>(2, 10, 1000)?, 24);
+        assert_eq!(mod_pow(3u64, 1_000_000_006, 1_000_000_007)?, 1);
+        assert_eq!(mod_pow(2u64, 64, u64::MAX)?, 1);
+        assert_eq!(mod_pow::<ires>(3, -1, 7)?, 5);
+        assert_eq!(mod_pow::<ures>(5, 0, 1)?, 0);
+        assert_eq!(mod_inv::<ures>(3, 7)?, 5);
+        assert!(matches!(mod_inv::<ures>(4, 8), Err(Error::Unsolvable(_))));
+        assert!(mod_mul::<ires>(1, 1, 0).is_err());
+
+        assert_eq!(mod_mul(i128::MAX - 1, i128::MAX - 2, i128::MAX)?, 2);
+        assert_eq!(mod_mul(i128::MIN, i128::MIN, i128::MAX)?, 1);
+        assert_eq!(mod_pow(2i128, 127, i128::MAX)?, 1);
+        assert!(ext_gcd(i128::MIN, 0).is_err());
+        for (a, b) in [
+            (i128::MIN, 3),
+            (i128::MIN, -1),
+            (i128::MAX, i128::MIN),
+            (5, i128::MIN),
+        ] {
+            let (g, x, y) = ext_gcd(a, b)?;
+            assert_eq!(a.wrapping_mul(x).wrapping_add(b.wrapping_mul(y)), g);
+            assert_eq!(g, 1);
+        }
+        assert!(lcm_all([i128::MIN]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn chinese_remainder() -> Result<(), Error> {
+        assert_eq!(crt::<ures>([(2, 3), (3, 5), (2, 7)])?, (23, 105));
+        assert_eq!(crt::<ures>([(2, 4), (4, 6)])?, (10, 12));
+        assert!(matches!(
+            crt::<ures>([(1, 4), (2, 6)]),
+            Err(Error::Unsolvable(_))
+        ));
+        assert_eq!(crt::<ires>([(-1, 101), (-1, 103)])?, (10402, 10403));
+        assert_eq!(crt::<ures>([])?, (0, 1));
+        assert!(crt::<u64>([(0, u64::MAX), (0, u64::MAX - 1)]).is_err());
+
+        assert_eq!(lcm_all::<ures>([4, 6, 10])?, 60);
+        assert_eq!(lcm_all::<ires>([-4, 6])?, 12);
+        assert_eq!(lcm_all::<ures>([])?, 1);
+        assert!(lcm_all::<u64>([u64::MAX, u64::MAX - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn roots() -> Result<(), Error> {
+        for n in 0..2000u64 {
+            let root = isqrt(n)?;
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(isqrt(u64::MAX)?, u32::MAX as u64);
+        assert!(isqrt::<ires>(-1).is_err());
+        for n in -2000..2000i64 {
+            let root = icbrt(n)?;
+            assert!(root.pow(3) <= n && (root + 1).pow(3) > n);
+        }
+        assert_eq!(icbrt(u64::MAX)?, 2642245);
+        assert_eq!(icbrt(i128::MAX)?, 5541191377756);
+        assert_eq!(icbrt(i128::MIN)?, -5541191377757);
+        assert_eq!(icbrt(-27i128)?, -3);
+        assert_eq!(isqrt(i128::MAX)?, 13043817825332782212);
+        Ok(())
+    }
+
+    #[test]
+    pub fn big_counts() -> Result<(), Error> {
+        for n in 0..=20 {
+            assert_eq!(factorial_big(n).to_u64(), factorial(n).map(|v| v as u64));
+        }
+        assert_eq!(factorial_big(25).to_string(), "15511210043330985984000000");
+        for m in 0..30 {
+            for n in 0..=m {
+                assert_eq!(choose_big(m, n)?.to_u64(), choose(m, n)?.map(|v| v as u64));
+            }
+        }
+        assert_eq!(
+            choose_big(100, 50)?.to_string(),
+            "100891344545564193334812497256"
+        );
+        assert!(choose_big(3, 4).is_err());
+        assert!(matches!(choose(3, 4), Err(Error::InvalidState(_))));
+        assert!(matches!(choose(0, 1), Err(Error::InvalidState(_))));
+        Ok(())
+    }
+
+    #[test]
+    pub fn primes() -> Result<(), Error> {
+        let sieve = (0..1000u64)
+            .filter(|n| *n >= 2 && (2..*n).all(|d| n % d != 0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            (0..1000u64)
+                .filter(|n| is_prime(*n).unwrap())
+                .collect::<Vec<_>>(),
+            sieve
+        );
+        assert!(is_prime(1_000_000_007u64)?);
+        assert!(is_prime(18446744073709551557u64)?);
+        assert!(!is_prime(3215031751u64)?);
+        assert!(!is_prime(-7i64)?);
+        assert!(!is_prime(u64::MAX)?);
+        assert!(is_prime::<i128>((1 << 100) + 277).is_err());
+        Ok(())
+    }
+}
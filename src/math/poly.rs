@@ -0,0 +1,225 @@
+use crate::error::Error;
+use crate::math::rational::Rational;
+use crate::math::MathInt;
+
+fn overflow() -> Error {
+    Error::InvalidState("overflow in polynomial arithmetic".into())
+}
+
+/// Finite difference table of equally spaced samples: the samples, their differences, the
+/// differences of those and so on, stopping after the first all zero row or a single value.
+#[allow(dead_code)]
+pub fn differences<T: MathInt>(samples: &[T]) -> Result<Vec<Vec<i128>>, Error> {
+    let mut rows = vec![samples.iter().map(|s| s.to_i128()).collect::<Vec<_>>()];
+    loop {
+        let last = rows.last().unwrap();
+        if last.len() <= 1 || last.iter().all(|v| *v == 0) {
+            return Ok(rows);
+        }
+        let next = last
+            .windows(2)
+            .map(|w| w[1].checked_sub(w[0]).ok_or_else(overflow))
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.push(next);
+    }
+}
+
+/// Degree of the lowest degree polynomial through the samples, taken at `0, 1, 2, ...`.
+/// `None` when there are too few samples to confirm it, i.e. no difference row is all zero.
+/// The zero polynomial has degree 0 here.
+#[allow(dead_code)]
+pub fn lowest_degree<T: MathInt>(samples: &[T]) -> Result<Option<usize>, Error> {
+    Ok(table_degree(&differences(samples)?))
+}
+
+fn table_degree(rows: &[Vec<i128>]) -> Option<usize> {
+    let last = rows.last().unwrap();
+    if last.is_empty() || last.iter().any(|v| *v != 0) {
+        return None;
+    }
+    Some(rows.len().saturating_sub(2))
+}
+
+/// Value at `n` of the lowest degree polynomial through the samples, taken at `0, 1, 2, ...`,
+/// by Newton's forward differences. Only integers are involved so large `n` works as long as
+/// the terms fit in `i128`.
+#[allow(dead_code)]
+pub fn extrapolate<T: MathInt>(samples: &[T], n: T) -> Result<T, Error> {
+    let rows = differences(samples)?;
+    let degree = table_degree(&rows)
+        .ok_or_else(|| Error::Unsolvable("too few samples to settle on a polynomial".into()))?;
+    let n = n.to_i128();
+    let mut binomial = 1i128;
+    let mut res = 0i128;
+    for (k, row) in rows.iter().enumerate().take(degree + 1) {
+        if k > 0 {
+            // a product of k consecutive integers is divisible by k!, so this stays exact
+            binomial = binomial
+                .checked_mul(n.checked_sub(k as i128 - 1).ok_or_else(overflow)?)
+                .ok_or_else(overflow)?
+                / k as i128;
+        }
+        let term = binomial.checked_mul(row[0]).ok_or_else(overflow)?;
+        res = res.checked_add(term).ok_or_else(overflow)?;
+    }
+    T::from_i128(res)
+}
+
+/// Polynomial with exact rational coefficients, lowest degree first.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Default)]
+pub struct Polynomial {
+    coeffs: Vec<Rational>,
+}
+
+#[allow(dead_code)]
+impl Polynomial {
+    pub fn from_coeffs(coeffs: impl IntoIterator<Item = Rational>) -> Self {
+        let mut coeffs = coeffs.into_iter().collect::<Vec<_>>();
+        while coeffs.last().is_some_and(Rational::is_zero) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    pub fn coeffs(&self) -> &[Rational] {
+        &self.coeffs
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Lagrange interpolation, the lowest degree polynomial through the points.
+    pub fn interpolate(points: &[(Rational, Rational)]) -> Result<Self, Error> {
+        let mut coeffs = vec![Rational::ZERO; points.len()];
+        for (i, (x_i, y_i)) in points.iter().enumerate() {
+            // basis polynomial, 1 at x_i and 0 at every other x
+            let mut basis = vec![Rational::ONE];
+            let mut scale = *y_i;
+            for (j, (x_j, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let diff = x_i.checked_sub(x_j)?;
+                if diff.is_zero() {
+                    return Err(Error::InvalidState(
+                        format!("duplicated x {} in interpolation points", x_i).into(),
+                    ));
+                }
+                scale = scale.checked_div(&diff)?;
+                basis = Self::mul_linear(&basis, x_j)?;
+            }
+            for (coeff, b) in coeffs.iter_mut().zip(basis.iter()) {
+                *coeff = coeff.checked_add(&b.checked_mul(&scale)?)?;
+            }
+        }
+        Ok(Self::from_coeffs(coeffs))
+    }
+
+    /// [`Polynomial::interpolate`] through integer points.
+    pub fn interpolate_int<T: MathInt>(points: &[(T, T)]) -> Result<Self, Error> {
+        let points = points
+            .iter()
+            .map(|(x, y)| (Rational::from_int(*x), Rational::from_int(*y)))
+            .collect::<Vec<_>>();
+        Self::interpolate(&points)
+    }
+
+    /// `poly * (x - root)`
+    fn mul_linear(poly: &[Rational], root: &Rational) -> Result<Vec<Rational>, Error> {
+        let mut res = vec![Rational::ZERO; poly.len() + 1];
+        for (i, coeff) in poly.iter().enumerate() {
+            res[i + 1] = res[i + 1].checked_add(coeff)?;
+            res[i] = res[i].checked_sub(&coeff.checked_mul(root)?)?;
+        }
+        Ok(res)
+    }
+
+    pub fn eval(&self, x: &Rational) -> Result<Rational, Error> {
+        self.coeffs
+            .iter()
+            .rev()
+            .try_fold(Rational::ZERO, |acc, coeff| {
+                acc.checked_mul(x)?.checked_add(coeff)
+            })
+    }
+
+    /// Value at an integer `x`, [`Error::Unsolvable`] when it is not an integer.
+    pub fn eval_int<T: MathInt>(&self, x: T) -> Result<T, Error> {
+        self.eval(&Rational::from_int(x))?.to_integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::poly::{differences, extrapolate, lowest_degree, Polynomial};
+    use crate::math::rational::Rational;
+    use crate::utils::ires;
+
+    #[test]
+    pub fn finite_differences() -> Result<(), Error> {
+        let samples = (0..6)
+            .map(|x: ires| 3 * x * x - 2 * x + 7)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            differences(&samples)?,
+            [
+                vec![7, 8, 15, 28, 47, 72],
+                vec![1, 7, 13, 19, 25],
+                vec![6, 6, 6, 6],
+                vec![0, 0, 0]
+            ]
+        );
+        assert_eq!(lowest_degree(&samples)?, Some(2));
+        assert_eq!(lowest_degree(&samples[..3])?, None);
+        assert_eq!(lowest_degree(&[5, 5])?, Some(0));
+        assert_eq!(lowest_degree::<ires>(&[0, 0])?, Some(0));
+
+        let n = 202300;
+        assert_eq!(extrapolate(&samples, n)?, 3 * n * n - 2 * n + 7);
+        assert_eq!(extrapolate(&samples, -4)?, 3 * 16 + 8 + 7);
+        assert!(matches!(
+            extrapolate(&samples[..3], n),
+            Err(Error::Unsolvable(_))
+        ));
+        assert!(extrapolate::<i64>(&[0, 1, 2], i64::MAX).is_ok());
+        assert!(extrapolate::<i64>(&[0, 1, 4, 9], i64::MAX).is_err());
+        assert_eq!(extrapolate::<i128>(&[0, 1, 2], i128::MIN)?, i128::MIN);
+        assert!(extrapolate::<i128>(&[0, 1, 4], i128::MIN).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn lagrange() -> Result<(), Error> {
+        let poly = Polynomial::interpolate_int::<ires>(&[(1, 1), (2, 3), (4, 2)])?;
+        assert_eq!(poly.degree(), Some(2));
+        // -5/6 x^2 + 9/2 x - 8/3
+        assert_eq!(
+            poly.coeffs(),
+            [
+                Rational::new(-8, 3)?,
+                Rational::new(9, 2)?,
+                Rational::new(-5, 6)?
+            ]
+        );
+        assert_eq!(poly.eval_int::<ires>(4)?, 2);
+        assert_eq!(poly.eval(&Rational::from(3))?, Rational::new(10, 3)?);
+        assert!(poly.eval_int::<ires>(3).is_err());
+
+        let cubic = Polynomial::interpolate_int::<ires>(&[(-1, -1), (0, 0), (1, 1), (2, 8)])?;
+        assert_eq!(cubic.degree(), Some(3));
+        assert_eq!(cubic.eval_int::<ires>(1000)?, 1_000_000_000);
+        let line = Polynomial::interpolate_int::<ires>(&[(0, 2), (1, 4), (2, 6)])?;
+        assert_eq!(line.degree(), Some(1));
+        assert_eq!(Polynomial::interpolate(&[])?.degree(), None);
+        assert!(Polynomial::interpolate_int::<ires>(&[(1, 1), (1, 2)]).is_err());
+        let (x1, x2) = (
+            Rational::new(1, (1 << 100) - 1)?,
+            Rational::new(1, (1 << 100) - 3)?,
+        );
+        assert!(Polynomial::interpolate(&[(x1, Rational::ONE), (x2, Rational::ONE)]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::error::Error;
+use crate::math::MathInt;
+use num_integer::Integer;
+use std::fmt::{Display, Formatter};
+
+/// Exact fraction over `i128`, always reduced with a positive denominator so equal values
+/// compare equal. Arithmetic is checked and fails instead of overflowing.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn overflow() -> Error {
+    Error::InvalidState("overflow in rational arithmetic".into())
+}
+
+#[allow(dead_code)]
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Result<Self, Error> {
+        if den == 0 {
+            return Err(Error::InvalidState(
+                format!("zero denominator in {}/{}", num, den).into(),
+            ));
+        }
+        // on the magnitudes since `i128::MIN` has none in `i128`
+        let gcd = num.unsigned_abs().gcd(&den.unsigned_abs());
+        let (num, den) = match i128::try_from(gcd) {
+            Ok(gcd) => (num / gcd, den / gcd),
+            // only 2^127 does not fit, both are then 0 or `i128::MIN` and even
+            Err(_) => {
+                let half = (gcd / 2) as i128;
+                (num / 2 / half, den / 2 / half)
+            }
+        };
+        if den < 0 {
+            Ok(Self {
+                num: num.checked_neg().ok_or_else(overflow)?,
+                den: den.checked_neg().ok_or_else(overflow)?,
+            })
+        } else {
+            Ok(Self { num, den })
+        }
+    }
+
+    pub fn from_int<T: MathInt>(val: T) -> Self {
+        Self::from(val.to_i128())
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /// The value as an integer, [`Error::Unsolvable`] when it has a fractional part.
+    pub fn to_integer<T: MathInt>(self) -> Result<T, Error> {
+        if !self.is_integer() {
+            return Err(Error::Unsolvable(
+                format!("{} is not an integer", self).into(),
+            ));
+        }
+        T::from_i128(self.num)
+    }
+
+    pub fn checked_neg(&self) -> Result<Self, Error> {
+        Ok(Self {
+            num: self.num.checked_neg().ok_or_else(overflow)?,
+            den: self.den,
+        })
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Error> {
+        let gcd = self.den.gcd(&other.den);
+        let lcm = (self.den / gcd)
+            .checked_mul(other.den)
+            .ok_or_else(overflow)?;
+        let lhs = self.num.checked_mul(lcm / self.den).ok_or_else(overflow)?;
+        let rhs = other
+            .num
+            .checked_mul(lcm / other.den)
+            .ok_or_else(overflow)?;
+        Self::new(lhs.checked_add(rhs).ok_or_else(overflow)?, lcm)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Error> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Error> {
+        // cross reduce first so the products stay as small as possible
+        let gcd_1 = self.num.gcd(&other.den).max(1);
+        let gcd_2 = other.num.gcd(&self.den).max(1);
+        let num = (self.num / gcd_1)
+            .checked_mul(other.num / gcd_2)
+            .ok_or_else(overflow)?;
+        let den = (self.den / gcd_2)
+            .checked_mul(other.den / gcd_1)
+            .ok_or_else(overflow)?;
+        Self::new(num, den)
+    }
+
+    pub fn recip(&self) -> Result<Self, Error> {
+        Self::new(self.den, self.num)
+    }
+
+    pub fn checked_div(&self, other: &Self) -> Result<Self, Error> {
+        self.checked_mul(&other.recip()?)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(num: i128) -> Self {
+        Self { num, den: 1 }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::math::rational::Rational;
+    use crate::utils::ires;
+
+    #[test]
+    pub fn arithmetic() -> Result<(), Error> {
+        let half = Rational::new(2, 4)?;
+        let third = Rational::new(-1, -3)?;
+        assert_eq!((half.numer(), half.denom()), (1, 2));
+        assert_eq!(third, Rational::new(1, 3)?);
+        assert_eq!(half.checked_add(&third)?, Rational::new(5, 6)?);
+        assert_eq!(half.checked_sub(&third)?, Rational::new(1, 6)?);
+        assert_eq!(half.checked_mul(&third)?, Rational::new(1, 6)?);
+        assert_eq!(half.checked_div(&third)?, Rational::new(3, 2)?);
+        assert_eq!(Rational::new(3, -6)?.to_string(), "-1/2");
+        assert_eq!(Rational::new(6, 3)?.to_integer::<ires>()?, 2);
+        assert!(half.to_integer::<ires>().is_err());
+        assert!(Rational::new(1, 0).is_err());
+        assert!(Rational::ZERO.recip().is_err());
+        assert!(Rational::from(i128::MAX)
+            .checked_add(&Rational::ONE)
+            .is_err());
+        let (a, b) = (
+            Rational::new(1, (1 << 100) - 1)?,
+            Rational::new(1, (1 << 100) - 3)?,
+        );
+        assert!(a.checked_add(&b).is_err());
+        assert!(a.checked_sub(&b).is_err());
+        assert_eq!(Rational::new(0, i128::MIN)?, Rational::ZERO);
+        assert_eq!(Rational::new(i128::MIN, i128::MIN)?, Rational::ONE);
+        assert_eq!(Rational::new(i128::MIN, 2)?.numer(), i128::MIN / 2);
+        assert!(Rational::new(1, i128::MIN).is_err());
+        let big = Rational::new(i128::MAX, 3)?;
+        assert_eq!(
+            big.checked_mul(&Rational::new(3, i128::MAX)?)?,
+            Rational::ONE
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::Error;
+use crate::hash::FastMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+/// Backing storage of a [`Memo`], values are cloned out on lookup.
+#[allow(dead_code)]
+pub trait MemoStore<K, V> {
+    fn get(&mut self, key: &K) -> Option<V>;
+    fn insert(&mut self, key: K, val: V) -> Result<(), Error>;
+    fn clear(&mut self);
+}
+
+impl<K, V, S> MemoStore<K, V> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: Clone,
+    S: BuildHasher,
+{
+    fn get(&mut self, key: &K) -> Option<V> {
+        HashMap::get(self, key).cloned()
+    }
+
+    fn insert(&mut self, key: K, val: V) -> Result<(), Error> {
+        HashMap::insert(self, key, val);
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
+
+/// Array indexed by integer keys below `bound`, grown lazily up to it.
+#[derive(Clone, Debug)]
+pub struct DenseStore<V> {
+    values: Vec<Option<V>>,
+    bound: usize,
+}
+
+#[allow(dead_code)]
+impl<V> DenseStore<V> {
+    pub fn with_bound(bound: usize) -> Self {
+        Self {
+            values: Vec::new(),
+            bound,
+        }
+    }
+}
+
+impl<K, V> MemoStore<K, V> for DenseStore<V>
+where
+    K: TryInto<usize> + Copy,
+    V: Clone,
+{
+    fn get(&mut self, key: &K) -> Option<V> {
+        let idx: usize = (*key).try_into().ok()?;
+        self.values.get(idx).cloned().flatten()
+    }
+
+    fn insert(&mut self, key: K, val: V) -> Result<(), Error> {
+        let idx = key
+            .try_into()
+            .ok()
+            .filter(|idx| *idx < self.bound)
+            .ok_or_else(|| Error::InvalidState("out of bound".into()))?;
+        if idx >= self.values.len() {
+            self.values.resize_with(idx + 1, || None);
+        }
+        self.values[idx] = Some(val);
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.values.clear()
+    }
+}
+
+/// Keeps at most `capacity` entries, evicting the least recently used one.
+#[derive(Clone, Debug)]
+pub struct LruStore<K, V> {
+    entries: FastMap<K, (V, u64)>,
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    capacity: usize,
+}
+
+#[allow(dead_code)]
+impl<K, V> LruStore<K, V> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: FastMap::default(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K, V> MemoStore<K, V> for LruStore<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    fn get(&mut self, key: &K) -> Option<V> {
+        let (val, last_used) = self.entries.get_mut(key)?;
+        self.recency.remove(last_used);
+        self.tick += 1;
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key.clone());
+        Some(val.clone())
+    }
+
+    fn insert(&mut self, key: K, val: V) -> Result<(), Error> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (val, self.tick)) {
+            self.recency.remove(&last_used);
+        } else if self.entries.len() > self.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.recency.insert(self.tick, key);
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[allow(dead_code)]
+impl MemoStats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "hits: {}, misses: {}, hit rate: {:.2}%",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// Cache of computed values counting its hits and misses, see [`Memo::recurse`] for
+/// memoizing a recursive function.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V, S = FastMap<K, V>> {
+    store: S,
+    stats: MemoStats,
+    phantom_data: PhantomData<(K, V)>,
+}
+
+impl<K, V, S: MemoStore<K, V>> From<S> for Memo<K, V, S> {
+    fn from(store: S) -> Self {
+        Self {
+            store,
+            stats: MemoStats::default(),
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S: MemoStore<K, V> + Default> Default for Memo<K, V, S> {
+    fn default() -> Self {
+        Self::from(S::default())
+    }
+}
+
+#[allow(dead_code)]
+impl<K, V, S: MemoStore<K, V>> Memo<K, V, S> {
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let res = self.store.get(key);
+        match res {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        res
+    }
+
+    pub fn insert(&mut self, key: K, val: V) -> Result<(), Error> {
+        self.store.insert(key, val)
+    }
+
+    pub fn get_or_try_insert_with<F>(&mut self, key: K, f: F) -> Result<V, Error>
+    where
+        V: Clone,
+        F: FnOnce(&mut Self) -> Result<V, Error>,
+    {
+        if let Some(val) = self.get(&key) {
+            return Ok(val);
+        }
+        let val = f(self)?;
+        self.insert(key, val.clone())?;
+        Ok(val)
+    }
+
+    /// Memoized call of the recursive function `f`, which receives a callback for its recursive
+    /// calls in place of calling itself.
+    ///
+    /// ```ignore
+    /// let fib = memo.recurse(90, &|fib, n| match *n {
+    ///     0 | 1 => Ok(*n),
+    ///     n => Ok(fib(n - 1)? + fib(n - 2)?),
+    /// })?;
+    /// ```
+    pub fn recurse<F>(&mut self, key: K, f: &F) -> Result<V, Error>
+    where
+        V: Clone,
+        F: Fn(&mut dyn FnMut(K) -> Result<V, Error>, &K) -> Result<V, Error>,
+    {
+        if let Some(val) = self.get(&key) {
+            return Ok(val);
+        }
+        let val = f(&mut |key| self.recurse(key, f), &key)?;
+        self.insert(key, val.clone())?;
+        Ok(val)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Drops every cached value, the statistics are kept.
+    pub fn clear(&mut self) {
+        self.store.clear()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = MemoStats::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::memo::{DenseStore, LruStore, Memo, MemoStats, MemoStore};
+    use crate::utils::ures;
+
+    fn fib<S: MemoStore<ures, ures>>(
+        memo: &mut Memo<ures, ures, S>,
+        n: ures,
+    ) -> Result<ures, Error> {
+        memo.recurse(n, &|fib, n| match *n {
+            0 | 1 => Ok(*n),
+            n => Ok(fib(n - 1)? + fib(n - 2)?),
+        })
+    }
+
+    #[test]
+    pub fn recursive_stores() -> Result<(), Error> {
+        let mut hash: Memo<_, _> = Memo::default();
+        assert_eq!(fib(&mut hash, 90)?, 2880067194370816120);
+        assert_eq!(
+            hash.stats(),
+            MemoStats {
+                hits: 88,
+                misses: 91
+            }
+        );
+
+        let mut dense = Memo::from(DenseStore::with_bound(91));
+        assert_eq!(fib(&mut dense, 90)?, 2880067194370816120);
+        assert_eq!(dense.stats(), hash.stats());
+        assert!(fib(&mut Memo::from(DenseStore::with_bound(10)), 20).is_err());
+
+        let mut lru = Memo::from(LruStore::with_capacity(3));
+        assert_eq!(fib(&mut lru, 90)?, 2880067194370816120);
+        assert_eq!(lru.stats(), hash.stats());
+        Ok(())
+    }
+
+    #[test]
+    pub fn lru_eviction() -> Result<(), Error> {
+        let mut lru = LruStore::with_capacity(2);
+        lru.insert('a', 1)?;
+        lru.insert('b', 2)?;
+        assert_eq!(lru.get(&'a'), Some(1));
+        lru.insert('c', 3)?;
+        assert_eq!(lru.get(&'b'), None);
+        assert_eq!(lru.get(&'a'), Some(1));
+        lru.insert('a', 4)?;
+        lru.insert('d', 5)?;
+        assert_eq!(lru.get(&'c'), None);
+        assert_eq!(lru.get(&'a'), Some(4));
+        assert_eq!(lru.len(), 2);
+        Ok(())
+    }
+}
@@ -1,19 +1,36 @@
 use crate::error::Error;
-use crate::space::Space;
+use crate::space::index::IndexMapping;
+use crate::space::space2d::Board2d;
+use crate::space::{IterSpace, Space, SpaceMut};
+use bit_set::BitSet;
 use derive_more::{Deref, DerefMut};
-use std::collections::HashSet;
-use std::hash::Hash;
+use std::borrow::{Borrow, BorrowMut};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
 use std::marker::PhantomData;
 
 #[allow(dead_code)]
 pub trait Set<T> {
     fn contains(&self, elem: &T) -> Result<bool, Error>;
     fn insert(&mut self, elem: T) -> Result<bool, Error>;
+    fn remove(&mut self, elem: &T) -> Result<bool, Error>;
+    /// Number of elements. The sets over a space scan all of it, O(W*H) for a board.
+    fn len(&self) -> usize;
+    /// Removes every element, also a full scan for the sets over a space.
+    fn clear(&mut self);
+    /// Elements by reference when the set stores them, by value when they are rebuilt from an
+    /// index, e.g. the positions of a [`BoolSpace`].
+    fn iter(&self) -> impl Iterator<Item = impl Borrow<T>>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
-impl<T> Set<T> for HashSet<T>
+impl<T, S> Set<T> for HashSet<T, S>
 where
     T: Eq + Hash,
+    S: BuildHasher,
 {
     fn contains(&self, elem: &T) -> Result<bool, Error> {
         Ok(self.contains(elem))
@@ -21,17 +38,29 @@ where
     fn insert(&mut self, elem: T) -> Result<bool, Error> {
         Ok(self.insert(elem))
     }
+    fn remove(&mut self, elem: &T) -> Result<bool, Error> {
+        Ok(self.remove(elem))
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    fn clear(&mut self) {
+        self.clear()
+    }
+    fn iter(&self) -> impl Iterator<Item = impl Borrow<T>> {
+        self.iter()
+    }
 }
 
 #[derive(Clone, Debug, Deref, DerefMut)]
-pub struct BoolSpace<I, B: Space<bool, I, N>, const N: usize> {
+pub struct BoolSpace<I, B: SpaceMut<bool, I, N>, const N: usize> {
     inner: B,
     #[deref(ignore)]
     #[deref_mut(ignore)]
     phantom_data_i: PhantomData<I>,
 }
 
-impl<I, B: Space<bool, I, N>, const N: usize> From<B> for BoolSpace<I, B, N> {
+impl<I, B: SpaceMut<bool, I, N>, const N: usize> From<B> for BoolSpace<I, B, N> {
     fn from(value: B) -> Self {
         Self {
             inner: value,
@@ -40,31 +69,59 @@ impl<I, B: Space<bool, I, N>, const N: usize> From<B> for BoolSpace<I, B, N> {
     }
 }
 
-impl<I, B: Space<bool, I, N>, const N: usize> Set<[I; N]> for BoolSpace<I, B, N> {
+impl<I, B, const N: usize> Set<[I; N]> for BoolSpace<I, B, N>
+where
+    B: SpaceMut<bool, I, N> + IterSpace<bool, I, N>,
+{
     fn contains(&self, elem: &[I; N]) -> Result<bool, Error> {
         self.get(elem)
             .ok_or_else(|| Error::InvalidState("out of bound".into()))
             .copied()
     }
     fn insert(&mut self, elem: [I; N]) -> Result<bool, Error> {
-        let val = self
+        let mut val = self
             .get_mut(&elem)
             .ok_or_else(|| Error::InvalidState("out of bound".into()))?;
         let previous_inserted = *val;
         *val = true;
         Ok(!previous_inserted)
     }
+    fn remove(&mut self, elem: &[I; N]) -> Result<bool, Error> {
+        self.set(elem, false)
+            .ok_or_else(|| Error::InvalidState("out of bound".into()))
+    }
+    /// No count is kept since the space can also be written through `DerefMut`.
+    fn len(&self) -> usize {
+        self.inner.iter().filter(|(_, val)| **val).count()
+    }
+    fn clear(&mut self) {
+        let inserted: Vec<_> = self
+            .inner
+            .iter()
+            .filter(|(_, val)| **val)
+            .map(|(elem, _)| elem)
+            .collect();
+        inserted.iter().for_each(|elem| {
+            self.set(elem, false);
+        });
+    }
+    fn iter(&self) -> impl Iterator<Item = impl Borrow<[I; N]>> {
+        self.inner
+            .iter()
+            .filter(|(_, val)| **val)
+            .map(|(elem, _)| elem)
+    }
 }
 
 #[derive(Clone, Debug, Deref, DerefMut)]
-pub struct OptionSpace<T, I, B: Space<Option<T>, I, N>, const N: usize> {
+pub struct OptionSpace<T, I, B: SpaceMut<Option<T>, I, N>, const N: usize> {
     inner: B,
     #[deref(ignore)]
     #[deref_mut(ignore)]
     phantom_data: PhantomData<(T, I)>,
 }
 
-impl<T, I, B: Space<Option<T>, I, N>, const N: usize> From<B> for OptionSpace<T, I, B, N> {
+impl<T, I, B: SpaceMut<Option<T>, I, N>, const N: usize> From<B> for OptionSpace<T, I, B, N> {
     fn from(value: B) -> Self {
         Self {
             inner: value,
@@ -73,16 +130,19 @@ impl<T, I, B: Space<Option<T>, I, N>, const N: usize> From<B> for OptionSpace<T,
     }
 }
 
-impl<T: Default, I, B: Space<Option<T>, I, N>, const N: usize> Set<[I; N]>
-    for OptionSpace<T, I, B, N>
+impl<T, I, B, const N: usize> Set<[I; N]> for OptionSpace<T, I, B, N>
+where
+    T: Default + 'static,
+    B: SpaceMut<Option<T>, I, N> + IterSpace<Option<T>, I, N>,
 {
     fn contains(&self, elem: &[I; N]) -> Result<bool, Error> {
-        self.get(elem)
+        self.inner
+            .get(elem)
             .ok_or_else(|| Error::InvalidState("out of bound".into()))
             .map(|o| o.is_some())
     }
     fn insert(&mut self, elem: [I; N]) -> Result<bool, Error> {
-        let val = self
+        let mut val = self
             .get_mut(&elem)
             .ok_or_else(|| Error::InvalidState("out of bound".into()))?;
         let previous_not_inserted = val.is_none();
@@ -91,4 +151,291 @@ impl<T: Default, I, B: Space<Option<T>, I, N>, const N: usize> Set<[I; N]>
         }
         Ok(previous_not_inserted)
     }
+    fn remove(&mut self, elem: &[I; N]) -> Result<bool, Error> {
+        Map::remove(self, elem).map(|val| val.is_some())
+    }
+    fn len(&self) -> usize {
+        Map::len(self)
+    }
+    fn clear(&mut self) {
+        Map::clear(self)
+    }
+    fn iter(&self) -> impl Iterator<Item = impl Borrow<[I; N]>> {
+        Map::iter(self).map(|(elem, _)| elem)
+    }
+}
+
+#[allow(dead_code)]
+pub trait Map<K, V> {
+    fn get(&self, key: &K) -> Result<Option<&V>, Error>;
+    fn insert(&mut self, key: K, val: V) -> Result<Option<V>, Error>;
+    fn remove(&mut self, key: &K) -> Result<Option<V>, Error>;
+    /// Number of entries. The maps over a space scan all of it, O(W*H) for a board.
+    fn len(&self) -> usize;
+    /// Removes every entry, also a full scan for the maps over a space.
+    fn clear(&mut self);
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (K, &'a V)>
+    where
+        V: 'a;
+
+    fn contains_key(&self, key: &K) -> Result<bool, Error> {
+        self.get(key).map(|val| val.is_some())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, V, S> Map<K, V> for HashMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn get(&self, key: &K) -> Result<Option<&V>, Error> {
+        Ok(self.get(key))
+    }
+    fn insert(&mut self, key: K, val: V) -> Result<Option<V>, Error> {
+        Ok(self.insert(key, val))
+    }
+    fn remove(&mut self, key: &K) -> Result<Option<V>, Error> {
+        Ok(self.remove(key))
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    fn clear(&mut self) {
+        self.clear()
+    }
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (K, &'a V)>
+    where
+        V: 'a,
+    {
+        self.iter().map(|(key, val)| (key.clone(), val))
+    }
+}
+
+fn space_get<'a, V, I, S: Space<Option<V>, I, N>, const N: usize>(
+    space: &'a S,
+    key: &[I; N],
+) -> Result<Option<&'a V>, Error> {
+    space
+        .get(key)
+        .ok_or_else(|| Error::InvalidState("out of bound".into()))
+        .map(Option::as_ref)
+}
+
+fn space_replace<V, I, S: SpaceMut<Option<V>, I, N>, const N: usize>(
+    space: &mut S,
+    key: &[I; N],
+    val: Option<V>,
+) -> Result<Option<V>, Error> {
+    space
+        .set(key, val)
+        .ok_or_else(|| Error::InvalidState("out of bound".into()))
+}
+
+fn space_clear<V: 'static, I, S, const N: usize>(space: &mut S)
+where
+    S: SpaceMut<Option<V>, I, N> + IterSpace<Option<V>, I, N>,
+{
+    let keys: Vec<_> = space
+        .iter()
+        .filter(|(_, val)| val.is_some())
+        .map(|(key, _)| key)
+        .collect();
+    keys.iter().for_each(|key| {
+        space.set(key, None);
+    });
+}
+
+impl<V: 'static, Vy, Vx> Map<[usize; 2], V> for Board2d<Option<V>, Vy, Vx>
+where
+    Vy: BorrowMut<[Vx]>,
+    Vx: BorrowMut<[Option<V>]>,
+{
+    fn get(&self, key: &[usize; 2]) -> Result<Option<&V>, Error> {
+        space_get(self, key)
+    }
+    fn insert(&mut self, key: [usize; 2], val: V) -> Result<Option<V>, Error> {
+        space_replace(self, &key, Some(val))
+    }
+    fn remove(&mut self, key: &[usize; 2]) -> Result<Option<V>, Error> {
+        space_replace(self, key, None)
+    }
+    fn len(&self) -> usize {
+        Map::iter(self).count()
+    }
+    fn clear(&mut self) {
+        space_clear(self)
+    }
+    fn iter<'a>(&'a self) -> impl Iterator<Item = ([usize; 2], &'a V)>
+    where
+        V: 'a,
+    {
+        IterSpace::iter(self).filter_map(|(key, val)| val.as_ref().map(|val| (key, val)))
+    }
+}
+
+impl<T, I, B, const N: usize> Map<[I; N], T> for OptionSpace<T, I, B, N>
+where
+    T: 'static,
+    B: SpaceMut<Option<T>, I, N> + IterSpace<Option<T>, I, N>,
+{
+    fn get(&self, key: &[I; N]) -> Result<Option<&T>, Error> {
+        space_get(&self.inner, key)
+    }
+    fn insert(&mut self, key: [I; N], val: T) -> Result<Option<T>, Error> {
+        space_replace(&mut self.inner, &key, Some(val))
+    }
+    fn remove(&mut self, key: &[I; N]) -> Result<Option<T>, Error> {
+        space_replace(&mut self.inner, key, None)
+    }
+    fn len(&self) -> usize {
+        Map::iter(self).count()
+    }
+    fn clear(&mut self) {
+        space_clear(&mut self.inner)
+    }
+    fn iter<'a>(&'a self) -> impl Iterator<Item = ([I; N], &'a T)>
+    where
+        T: 'a,
+    {
+        self.inner
+            .iter()
+            .filter_map(|(key, val)| val.as_ref().map(|val| (key, val)))
+    }
+}
+
+/// Set of keys stored as bits at their [`IndexMapping`] index, keys outside the mapping are out
+/// of bound.
+#[derive(Clone, Debug)]
+pub struct BitIndexSet<K, M: IndexMapping<K>> {
+    bits: BitSet,
+    mapping: M,
+    phantom_data: PhantomData<K>,
+}
+
+impl<K, M: IndexMapping<K>> From<M> for BitIndexSet<K, M> {
+    fn from(mapping: M) -> Self {
+        Self {
+            bits: BitSet::with_capacity(mapping.len()),
+            mapping,
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<K, M: IndexMapping<K>> BitIndexSet<K, M> {
+    fn index_of(&self, elem: &K) -> Result<usize, Error> {
+        self.mapping
+            .index_of(elem)
+            .ok_or_else(|| Error::InvalidState("out of bound".into()))
+    }
+}
+
+impl<K, M: IndexMapping<K>> Set<K> for BitIndexSet<K, M> {
+    fn contains(&self, elem: &K) -> Result<bool, Error> {
+        Ok(self.bits.contains(self.index_of(elem)?))
+    }
+    fn insert(&mut self, elem: K) -> Result<bool, Error> {
+        let index = self.index_of(&elem)?;
+        Ok(self.bits.insert(index))
+    }
+    fn remove(&mut self, elem: &K) -> Result<bool, Error> {
+        let index = self.index_of(elem)?;
+        Ok(self.bits.remove(index))
+    }
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+    fn clear(&mut self) {
+        self.bits.clear()
+    }
+    fn iter(&self) -> impl Iterator<Item = impl Borrow<K>> {
+        self.bits
+            .iter()
+            .filter_map(|index| self.mapping.key_of(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::set::{BitIndexSet, BoolSpace, Map, OptionSpace, Set};
+    use crate::space::index::{DirectionIndex, GridIndex, PairIndex};
+    use crate::space::space2d::{BitBoard2d, Board2d, Direction};
+    use std::borrow::Borrow;
+    use std::collections::{HashMap, HashSet};
+
+    fn exercise_set<S: Set<[usize; 2]>>(mut set: S) -> Result<(), Error> {
+        assert!(set.insert([1, 0])?);
+        assert!(set.insert([0, 1])?);
+        assert!(!set.insert([1, 0])?);
+        assert_eq!(set.len(), 2);
+        assert!(set.remove(&[1, 0])?);
+        assert!(!set.remove(&[1, 0])?);
+        assert!(!set.contains(&[1, 0])?);
+        assert_eq!(
+            set.iter().map(|e| *e.borrow()).collect::<Vec<_>>(),
+            [[0, 1]]
+        );
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.contains(&[0, 1])?);
+        Ok(())
+    }
+
+    fn exercise_map<M: Map<[usize; 2], u32>>(mut map: M) -> Result<(), Error> {
+        assert_eq!(map.insert([1, 0], 3)?, None);
+        assert_eq!(map.insert([1, 0], 4)?, Some(3));
+        assert_eq!(map.insert([0, 1], 5)?, None);
+        assert_eq!(map.get(&[1, 0])?, Some(&4));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.remove(&[0, 1])?, Some(5));
+        assert!(!map.contains_key(&[0, 1])?);
+        assert_eq!(map.iter().collect::<Vec<_>>(), [([1, 0], &4)]);
+        map.clear();
+        assert!(map.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    pub fn sets() -> Result<(), Error> {
+        exercise_set(HashSet::new())?;
+        exercise_set(BoolSpace::from(Board2d::from([[false; 2]; 2])))?;
+        exercise_set(BoolSpace::from(BitBoard2d::<usize>::with_height(2)))?;
+        exercise_set(OptionSpace::<(), _, _, 2>::from(Board2d::from(
+            [[None; 2]; 2],
+        )))?;
+        assert!(BoolSpace::from(Board2d::from([[false; 2]; 2]))
+            .remove(&[2, 0])
+            .is_err());
+
+        let mut states =
+            BitIndexSet::from(PairIndex::from((GridIndex::from([2, 2]), DirectionIndex)));
+        assert!(states.insert(([1, 1], Direction::West))?);
+        assert!(!states.contains(&([1, 1], Direction::East))?);
+        assert!(states.insert(([0, 2], Direction::West)).is_err());
+
+        // elements are only borrowed, so they need not be `Clone`
+        #[derive(Eq, PartialEq, Hash, Debug)]
+        struct Token(u8);
+        let mut tokens = HashSet::new();
+        assert!(Set::insert(&mut tokens, Token(1))?);
+        assert_eq!(
+            Set::iter(&tokens).map(|t| t.borrow().0).collect::<Vec<_>>(),
+            [1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn maps() -> Result<(), Error> {
+        exercise_map(HashMap::new())?;
+        exercise_map(Board2d::from([[None; 2]; 2]))?;
+        exercise_map(OptionSpace::from(Board2d::from([[None; 2]; 2])))?;
+        assert!(Map::insert(&mut Board2d::from([[None; 2]; 2]), [0, 2], 1).is_err());
+        Ok(())
+    }
 }
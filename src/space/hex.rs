@@ -0,0 +1,283 @@
+use crate::space::{IterMutSpace, IterSpace, Space, SpaceMut};
+use derive_more::{Add, AddAssign, Deref, DerefMut, Display, Neg, Sub, SubAssign};
+use std::collections::HashMap;
+use std::iter;
+
+/// Axial hex coordinate, the implied cube coordinate is `s = -q - r`.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Default, Add, AddAssign, Sub, SubAssign, Neg)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Default, Add, Sub, Neg)]
+pub struct Cube {
+    pub q: isize,
+    pub r: isize,
+    pub s: isize,
+}
+
+/// Named for pointy-top layout, for flat-top layout every direction is 30 degree
+/// counter-clockwise of its name, e.g. `East` is north-east.
+#[derive(Eq, PartialEq, Hash, Debug, Display, Clone, Copy)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+#[allow(dead_code)]
+impl HexDirection {
+    pub const fn get_movement_vec(&self) -> Hex {
+        match self {
+            HexDirection::East => Hex::new(1, 0),
+            HexDirection::NorthEast => Hex::new(1, -1),
+            HexDirection::NorthWest => Hex::new(0, -1),
+            HexDirection::West => Hex::new(-1, 0),
+            HexDirection::SouthWest => Hex::new(-1, 1),
+            HexDirection::SouthEast => Hex::new(0, 1),
+        }
+    }
+
+    pub const fn clockwise_60(&self) -> Self {
+        match self {
+            HexDirection::East => HexDirection::SouthEast,
+            HexDirection::NorthEast => HexDirection::East,
+            HexDirection::NorthWest => HexDirection::NorthEast,
+            HexDirection::West => HexDirection::NorthWest,
+            HexDirection::SouthWest => HexDirection::West,
+            HexDirection::SouthEast => HexDirection::SouthWest,
+        }
+    }
+
+    pub const fn counter_clockwise_60(&self) -> Self {
+        match self {
+            HexDirection::East => HexDirection::NorthEast,
+            HexDirection::NorthEast => HexDirection::NorthWest,
+            HexDirection::NorthWest => HexDirection::West,
+            HexDirection::West => HexDirection::SouthWest,
+            HexDirection::SouthWest => HexDirection::SouthEast,
+            HexDirection::SouthEast => HexDirection::East,
+        }
+    }
+
+    pub const fn opposite(&self) -> Self {
+        match self {
+            HexDirection::East => HexDirection::West,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+            HexDirection::West => HexDirection::East,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+        }
+    }
+
+    /// Counter-clockwise starting from `East`.
+    pub const fn all() -> &'static [HexDirection] {
+        &[
+            Self::East,
+            Self::NorthEast,
+            Self::NorthWest,
+            Self::West,
+            Self::SouthWest,
+            Self::SouthEast,
+        ]
+    }
+}
+
+#[allow(dead_code)]
+impl Hex {
+    pub const fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    pub const fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn scale(&self, factor: isize) -> Self {
+        Self::new(self.q * factor, self.r * factor)
+    }
+
+    pub fn neighbor(&self, direction: HexDirection) -> Self {
+        *self + direction.get_movement_vec()
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = Hex> + use<> {
+        let hex = *self;
+        HexDirection::all()
+            .iter()
+            .map(move |direction| hex.neighbor(*direction))
+    }
+
+    pub fn length(&self) -> usize {
+        (self.q.unsigned_abs() + self.r.unsigned_abs() + self.s().unsigned_abs()) / 2
+    }
+
+    pub fn distance(&self, other: &Self) -> usize {
+        (*self - *other).length()
+    }
+
+    /// Every hex at exactly `radius` steps, walking counter-clockwise from the south-west corner.
+    pub fn ring(&self, radius: usize) -> impl Iterator<Item = Hex> + use<> {
+        let center = *self;
+        let start = center
+            + HexDirection::SouthWest
+                .get_movement_vec()
+                .scale(radius as isize);
+        let walk = HexDirection::all()
+            .iter()
+            .flat_map(move |direction| iter::repeat_n(*direction, radius))
+            .scan(start, |current, direction| {
+                let res = *current;
+                *current = current.neighbor(direction);
+                Some(res)
+            });
+        iter::once(center).filter(move |_| radius == 0).chain(walk)
+    }
+
+    /// Rings from `0` to `radius` inclusive, starting from the center.
+    pub fn spiral(&self, radius: usize) -> impl Iterator<Item = Hex> + use<> {
+        let center = *self;
+        (0..=radius).flat_map(move |r| center.ring(r))
+    }
+}
+
+impl From<[isize; 2]> for Hex {
+    fn from([q, r]: [isize; 2]) -> Self {
+        Self::new(q, r)
+    }
+}
+
+impl From<Hex> for [isize; 2] {
+    fn from(value: Hex) -> Self {
+        [value.q, value.r]
+    }
+}
+
+impl From<Hex> for Cube {
+    fn from(value: Hex) -> Self {
+        Self {
+            q: value.q,
+            r: value.r,
+            s: value.s(),
+        }
+    }
+}
+
+impl From<Cube> for Hex {
+    fn from(value: Cube) -> Self {
+        Self::new(value.q, value.r)
+    }
+}
+
+/// Sparse hex space keyed by axial `[q, r]`, any coordinate can be set.
+#[derive(Clone, Debug, Default, Deref, DerefMut)]
+pub struct HexSpace<T> {
+    inner: HashMap<Hex, T>,
+}
+
+impl<T> From<HashMap<Hex, T>> for HexSpace<T> {
+    fn from(value: HashMap<Hex, T>) -> Self {
+        Self { inner: value }
+    }
+}
+
+impl<T> Space<T, isize, 2> for HexSpace<T> {
+    fn get(&self, idx: &[isize; 2]) -> Option<&T> {
+        self.inner.get(&Hex::from(*idx))
+    }
+}
+
+impl<T> SpaceMut<T, isize, 2> for HexSpace<T> {
+    type RefMut<'a>
+        = &'a mut T
+    where
+        Self: 'a;
+
+    fn set(&mut self, idx: &[isize; 2], val: T) -> Option<T> {
+        self.inner.insert(Hex::from(*idx), val)
+    }
+
+    fn get_mut(&mut self, idx: &[isize; 2]) -> Option<&mut T> {
+        self.inner.get_mut(&Hex::from(*idx))
+    }
+
+    fn swap(&mut self, idx1: &[isize; 2], idx2: &[isize; 2]) -> bool {
+        let (hex1, hex2) = (Hex::from(*idx1), Hex::from(*idx2));
+        if !self.inner.contains_key(&hex1) || !self.inner.contains_key(&hex2) {
+            return false;
+        }
+        if hex1 != hex2 {
+            let val1 = self.inner.remove(&hex1).unwrap();
+            let val2 = self.inner.insert(hex2, val1).unwrap();
+            self.inner.insert(hex1, val2);
+        }
+        true
+    }
+}
+
+impl<T> IterSpace<T, isize, 2> for HexSpace<T> {
+    fn iter(&self) -> impl Iterator<Item = ([isize; 2], &T)> {
+        self.inner.iter().map(|(hex, val)| ((*hex).into(), val))
+    }
+}
+
+impl<T> IterMutSpace<T, isize, 2> for HexSpace<T> {
+    fn iter_mut(&mut self) -> impl Iterator<Item = ([isize; 2], &mut T)> {
+        self.inner.iter_mut().map(|(hex, val)| ((*hex).into(), val))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::space::hex::{Cube, Hex, HexDirection, HexSpace};
+    use crate::space::{IterSpace, Space, SpaceMut};
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn distance_and_direction() {
+        let origin = Hex::default();
+        for direction in HexDirection::all() {
+            assert_eq!(origin.neighbor(*direction).length(), 1);
+            assert_eq!(
+                origin.neighbor(*direction).neighbor(direction.opposite()),
+                origin
+            );
+            assert_eq!(direction.clockwise_60().counter_clockwise_60(), *direction);
+        }
+        let hex = Hex::new(3, -7);
+        assert_eq!(hex.distance(&origin), 7);
+        assert_eq!(Hex::from(Cube::from(hex)), hex);
+        assert_eq!(Cube::from(hex).s, 4);
+    }
+
+    #[test]
+    pub fn ring_and_spiral() {
+        let center = Hex::new(2, -1);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), [center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius).collect::<Vec<_>>();
+            assert_eq!(ring.len(), 6 * radius);
+            assert!(ring.iter().all(|hex| hex.distance(&center) == radius));
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+        }
+        assert_eq!(center.spiral(3).count(), 1 + 6 + 12 + 18);
+    }
+
+    #[test]
+    pub fn sparse_space() {
+        let mut space = HexSpace::default();
+        assert_eq!(space.set(&[0, 0], 'a'), None);
+        assert_eq!(space.set(&[-3, 5], 'b'), None);
+        assert!(space.swap(&[0, 0], &[-3, 5]));
+        assert!(!space.swap(&[0, 0], &[1, 1]));
+        *space.get_mut(&[0, 0]).unwrap() = 'c';
+        assert_eq!(space.get(&[0, 0]), Some(&'c'));
+        assert_eq!(space.get(&[-3, 5]), Some(&'a'));
+        assert_eq!(space.iter().count(), 2);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::space::space2d::Direction;
+use crate::space::Dimension;
+use derive_more::From;
+
+/// Bijection between keys and the dense indices `0..len()`, for structures storing their
+/// elements in flat arrays.
+#[allow(dead_code)]
+pub trait IndexMapping<K> {
+    fn len(&self) -> usize;
+    fn index_of(&self, key: &K) -> Option<usize>;
+    fn key_of(&self, index: usize) -> Option<K>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Row-major indices of the positions inside `dimension`, the first coordinate changing fastest.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, From)]
+pub struct GridIndex<const N: usize> {
+    pub dimension: [usize; N],
+}
+
+impl<const N: usize> Dimension<N> for GridIndex<N> {
+    fn dimension(&self) -> [usize; N] {
+        self.dimension
+    }
+}
+
+impl<const N: usize> IndexMapping<[usize; N]> for GridIndex<N> {
+    fn len(&self) -> usize {
+        self.dimension.iter().product()
+    }
+
+    fn index_of(&self, key: &[usize; N]) -> Option<usize> {
+        key.iter()
+            .zip(self.dimension.iter())
+            .rev()
+            .try_fold(0, |index, (val, size)| {
+                (val < size).then_some(index * size + val)
+            })
+    }
+
+    fn key_of(&self, mut index: usize) -> Option<[usize; N]> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut key = [0; N];
+        for (val, size) in key.iter_mut().zip(self.dimension.iter()) {
+            *val = index % size;
+            index /= size;
+        }
+        Some(key)
+    }
+}
+
+/// Indices of the four cardinal [`Direction`]s.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Default)]
+pub struct DirectionIndex;
+
+impl IndexMapping<Direction> for DirectionIndex {
+    fn len(&self) -> usize {
+        Direction::cardinal().len()
+    }
+
+    fn index_of(&self, key: &Direction) -> Option<usize> {
+        Direction::cardinal()
+            .iter()
+            .position(|direction| direction == key)
+    }
+
+    fn key_of(&self, index: usize) -> Option<Direction> {
+        Direction::cardinal().get(index).copied()
+    }
+}
+
+/// Indices of pairs, the second key changing fastest.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, From)]
+pub struct PairIndex<A, B>(pub A, pub B);
+
+impl<KA, KB, A: IndexMapping<KA>, B: IndexMapping<KB>> IndexMapping<(KA, KB)> for PairIndex<A, B> {
+    fn len(&self) -> usize {
+        self.0.len() * self.1.len()
+    }
+
+    fn index_of(&self, key: &(KA, KB)) -> Option<usize> {
+        let (a, b) = key;
+        Some(self.0.index_of(a)? * self.1.len() + self.1.index_of(b)?)
+    }
+
+    fn key_of(&self, index: usize) -> Option<(KA, KB)> {
+        if self.1.is_empty() {
+            return None;
+        }
+        Some((
+            self.0.key_of(index / self.1.len())?,
+            self.1.key_of(index % self.1.len())?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::space::index::{DirectionIndex, GridIndex, IndexMapping, PairIndex};
+    use crate::space::space2d::Direction;
+
+    #[test]
+    pub fn grid_index() {
+        let grid = GridIndex::from([3, 2]);
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid.index_of(&[2, 1]), Some(5));
+        assert_eq!(grid.index_of(&[1, 1]), Some(4));
+        assert_eq!(grid.index_of(&[3, 0]), None);
+        assert_eq!(grid.key_of(4), Some([1, 1]));
+        assert_eq!(grid.key_of(6), None);
+        assert!((0..grid.len())
+            .all(|index| grid.key_of(index).and_then(|key| grid.index_of(&key)) == Some(index)));
+    }
+
+    #[test]
+    pub fn pair_index() {
+        let pair = PairIndex::from((GridIndex::from([3, 2]), DirectionIndex));
+        assert_eq!(pair.len(), 24);
+        let index = pair.index_of(&([2, 1], Direction::South)).unwrap();
+        assert_eq!(pair.key_of(index), Some(([2, 1], Direction::South)));
+        assert_eq!(pair.index_of(&([0, 2], Direction::North)), None);
+        assert!((0..pair.len())
+            .all(|index| pair.key_of(index).and_then(|key| pair.index_of(&key)) == Some(index)));
+        assert_eq!(pair.key_of(24), None);
+    }
+}
@@ -1,35 +1,37 @@
-use std::ptr::from_mut;
+use std::ops::DerefMut;
+pub mod hex;
+pub mod index;
+pub mod region;
 pub mod space2d;
+pub mod view;
 
 pub trait Space<T, C, const N: usize> {
     fn get(&self, idx: &[C; N]) -> Option<&T>;
+}
+
+pub trait SpaceMut<T, C, const N: usize>: Space<T, C, N> {
+    type RefMut<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
     fn set(&mut self, idx: &[C; N], val: T) -> Option<T>;
-    fn get_mut(&mut self, idx: &[C; N]) -> Option<&mut T>;
-    fn swap(&mut self, idx1: &[C; N], idx2: &[C; N]) -> bool {
-        if let Some(ptr1) = self.get_mut(idx1).map(from_mut) {
-            if let Some(ptr2) = self.get_mut(idx2).map(from_mut) {
-                unsafe {
-                    if ptr1 != ptr2 {
-                        std::ptr::swap(ptr1, ptr2);
-                    }
-                }
-                return true;
-            }
-        }
-
-        false
-    }
+    fn get_mut(&mut self, idx: &[C; N]) -> Option<Self::RefMut<'_>>;
+    fn swap(&mut self, idx1: &[C; N], idx2: &[C; N]) -> bool;
+}
+
+pub trait Dimension<const N: usize> {
+    fn dimension(&self) -> [usize; N];
 }
 
 pub trait IterMutSpace<T, C, const N: usize> {
     #[allow(dead_code)]
-    fn iter_mut(&mut self) -> impl Iterator<Item = ([usize; 2], &mut T)>
+    fn iter_mut(&mut self) -> impl Iterator<Item = ([C; N], &mut T)>
     where
         T: 'static;
 }
 
 pub trait IterSpace<T, C, const N: usize> {
-    fn iter(&self) -> impl Iterator<Item = ([usize; 2], &T)>
+    fn iter(&self) -> impl Iterator<Item = ([C; N], &T)>
     where
         T: 'static;
 }
@@ -0,0 +1,356 @@
+use crate::error::Error;
+use crate::graph::MaybeProcessed::Processed;
+use crate::graph::{Bfs, NeighborFn};
+use crate::set::{BoolSpace, Set};
+use crate::space::space2d::{Board2d, Direction8};
+use crate::space::{IterSpace, Pos, Space, SpaceMut};
+use crate::utils::ures;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    const fn directions(&self) -> &'static [Direction8] {
+        match self {
+            Connectivity::Four => Direction8::cardinal(),
+            Connectivity::Eight => Direction8::all(),
+        }
+    }
+
+    const fn dual(&self) -> Self {
+        match self {
+            Connectivity::Four => Connectivity::Eight,
+            Connectivity::Eight => Connectivity::Four,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Region {
+    pub id: usize,
+    pub cells: Vec<[usize; 2]>,
+    /// Inclusive `(min, max)` corners.
+    pub bounding_box: ([usize; 2], [usize; 2]),
+}
+
+#[derive(Clone, Debug)]
+pub struct Regions {
+    labels: Board2d<Option<usize>>,
+    regions: Vec<Region>,
+    connectivity: Connectivity,
+}
+
+struct RegionNeighbor<'a, T, S, P, F> {
+    space: &'a S,
+    connectivity: Connectivity,
+    include: P,
+    connected: F,
+    phantom_data: PhantomData<T>,
+}
+
+impl<T, S, P, F> NeighborFn<([usize; 2], ())> for RegionNeighbor<'_, T, S, P, F>
+where
+    S: Space<T, usize, 2>,
+    P: FnMut(&T) -> bool,
+    F: FnMut(&T, &T) -> bool,
+{
+    fn get_neighbors(
+        &mut self,
+        sm: &([usize; 2], ()),
+    ) -> impl IntoIterator<Item = ([usize; 2], ())> {
+        let (pos, _) = sm;
+        let space = self.space;
+        let val = space.get(pos);
+        self.connectivity
+            .directions()
+            .iter()
+            .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+            .filter_map(|next_pos| space.get(&next_pos).map(|next_val| (next_pos, next_val)))
+            .filter(|(_, next_val)| {
+                val.is_some_and(|val| (self.include)(next_val) && (self.connected)(val, next_val))
+            })
+            .map(|(next_pos, _)| (next_pos, ()))
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Labels every cell with the same value as its neighbor into one region.
+#[allow(dead_code)]
+pub fn label_equal<T, S>(space: &S, connectivity: Connectivity) -> Result<Regions, Error>
+where
+    T: PartialEq + 'static,
+    S: Space<T, usize, 2> + IterSpace<T, usize, 2>,
+{
+    label_with(space, connectivity, |_| true, |a, b| a == b)
+}
+
+/// Labels connected cells satisfying `predicate`, other cells are left without a label.
+#[allow(dead_code)]
+pub fn label_where<T, S, P>(
+    space: &S,
+    connectivity: Connectivity,
+    predicate: P,
+) -> Result<Regions, Error>
+where
+    T: 'static,
+    S: Space<T, usize, 2> + IterSpace<T, usize, 2>,
+    P: FnMut(&T) -> bool,
+{
+    label_with(space, connectivity, predicate, |_, _| true)
+}
+
+/// Labels cells satisfying `include`, where two neighboring included cells `a` and `b` belong to
+/// the same region when `connected(a, b)` holds.
+pub fn label_with<T, S, P, F>(
+    space: &S,
+    connectivity: Connectivity,
+    include: P,
+    connected: F,
+) -> Result<Regions, Error>
+where
+    T: 'static,
+    S: Space<T, usize, 2> + IterSpace<T, usize, 2>,
+    P: FnMut(&T) -> bool,
+    F: FnMut(&T, &T) -> bool,
+{
+    let [width, height] = space.iter().fold([0, 0], |[width, height], ([x, y], _)| {
+        [width.max(x + 1), height.max(y + 1)]
+    });
+    let mut labels = Board2d::from(vec![vec![None; width]; height]);
+    let mut regions = Vec::new();
+    let mut bfs = Bfs {
+        queue: VecDeque::new(),
+        neighbor_fn: RegionNeighbor {
+            space,
+            connectivity,
+            include,
+            connected,
+            phantom_data: PhantomData,
+        },
+        visited: BoolSpace::from(Board2d::from(vec![vec![false; width]; height])),
+    };
+
+    for (pos, val) in space.iter() {
+        if bfs.visited.contains(&pos)? || !(bfs.neighbor_fn.include)(val) {
+            continue;
+        }
+        let id = regions.len();
+        let mut region = Region {
+            id,
+            cells: Vec::new(),
+            bounding_box: (pos, pos),
+        };
+        bfs.queue.push_back((pos, ()));
+        for res in bfs.by_ref() {
+            if let Processed((cell, _)) = res? {
+                labels.set(&cell, Some(id));
+                let ([min_x, min_y], [max_x, max_y]) = &mut region.bounding_box;
+                let [x, y] = cell;
+                *min_x = (*min_x).min(x);
+                *min_y = (*min_y).min(y);
+                *max_x = (*max_x).max(x);
+                *max_y = (*max_y).max(y);
+                region.cells.push(cell);
+            }
+        }
+        regions.push(region);
+    }
+
+    Ok(Regions {
+        labels,
+        regions,
+        connectivity,
+    })
+}
+
+#[allow(dead_code)]
+impl Regions {
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn label(&self, pos: &[usize; 2]) -> Option<usize> {
+        self.labels.get(pos).copied().flatten()
+    }
+
+    pub fn labels(&self) -> &Board2d<Option<usize>> {
+        &self.labels
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Region> {
+        self.regions.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter()
+    }
+
+    fn is_member(&self, region: &Region, pos: Option<[usize; 2]>) -> bool {
+        pos.and_then(|pos| self.label(&pos)) == Some(region.id)
+    }
+
+    pub fn area(&self, region: &Region) -> ures {
+        region.cells.len() as ures
+    }
+
+    /// Number of cell edges shared with a cell outside the region.
+    pub fn perimeter(&self, region: &Region) -> ures {
+        region
+            .cells
+            .iter()
+            .flat_map(|pos| {
+                Direction8::cardinal().iter().filter(|direction| {
+                    !self.is_member(region, pos.shift(direction.get_movement_vec()))
+                })
+            })
+            .count() as ures
+    }
+
+    /// Number of convex and concave corners of the region outline, holes included. Only defined
+    /// for 4-connected regions, cells of an 8-connected one may only touch at a corner.
+    pub fn corners(&self, region: &Region) -> Result<ures, Error> {
+        if self.connectivity == Connectivity::Eight {
+            return Err(Error::InvalidState(
+                "corners of an 8-connected region are not a rectilinear outline".into(),
+            ));
+        }
+        let corners = region
+            .cells
+            .iter()
+            .map(|pos| {
+                Direction8::diagonal()
+                    .iter()
+                    .filter(|diagonal| {
+                        let member = |direction: Direction8| {
+                            self.is_member(region, pos.shift(direction.get_movement_vec()))
+                        };
+                        let side_1 = member(diagonal.counter_clockwise_45());
+                        let side_2 = member(diagonal.clockwise_45());
+                        (!side_1 && !side_2) || (side_1 && side_2 && !member(**diagonal))
+                    })
+                    .count() as ures
+            })
+            .sum();
+        Ok(corners)
+    }
+
+    /// A rectilinear outline has as many straight sides as it has corners, so this is also only
+    /// defined for 4-connected regions.
+    pub fn sides(&self, region: &Region) -> Result<ures, Error> {
+        self.corners(region)
+    }
+
+    /// Number of connected groups of non-member cells fully enclosed by the region. Enclosed cells
+    /// are connected with the dual connectivity, so a 4-connected region only encloses cells that
+    /// cannot escape diagonally either.
+    pub fn holes(&self, region: &Region) -> ures {
+        let ([min_x, min_y], [max_x, max_y]) = region.bounding_box;
+        // One cell of padding on every side so the outside is a single connected component,
+        // shifted by one to stay in `usize`.
+        let width = max_x - min_x + 3;
+        let height = max_y - min_y + 3;
+        let member = |[x, y]: [usize; 2]| {
+            (1..width - 1).contains(&x)
+                && (1..height - 1).contains(&y)
+                && self.is_member(region, Some([x + min_x - 1, y + min_y - 1]))
+        };
+        let mut visited = vec![vec![false; width]; height];
+        let flood = |start: [usize; 2], visited: &mut Vec<Vec<bool>>| {
+            let mut work = vec![start];
+            visited[start[1]][start[0]] = true;
+            while let Some(pos) = work.pop() {
+                self.connectivity
+                    .dual()
+                    .directions()
+                    .iter()
+                    .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+                    .filter(|&[x, y]| x < width && y < height)
+                    .for_each(|next @ [x, y]| {
+                        if !visited[y][x] && !member(next) {
+                            visited[y][x] = true;
+                            work.push(next);
+                        }
+                    });
+            }
+        };
+
+        flood([0, 0], &mut visited);
+        let mut holes = 0;
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                if !visited[y][x] && !member([x, y]) {
+                    flood([x, y], &mut visited);
+                    holes += 1;
+                }
+            }
+        }
+
+        holes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::space::region::{label_equal, label_where, Connectivity};
+    use crate::space::space2d::RefBoard2d;
+    use std::borrow::Cow;
+
+    fn parse_board(input: &str) -> RefBoard2d<'_, u8> {
+        input
+            .lines()
+            .map(|line| Cow::Borrowed(line.as_bytes()))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    pub fn area_perimeter_sides() -> Result<(), Error> {
+        let board = parse_board("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n");
+        let regions = label_equal(&board, Connectivity::Four)?;
+        assert_eq!(regions.len(), 3);
+        let a = regions.get(regions.label(&[0, 0]).unwrap()).unwrap();
+        assert_eq!(regions.area(a), 28);
+        assert_eq!(regions.perimeter(a), 40);
+        assert_eq!(regions.sides(a)?, 12);
+        // both `B` blocks touch diagonally, which is one hole for a 4-connected region
+        assert_eq!(regions.holes(a), 1);
+        assert_eq!(a.bounding_box, ([0, 0], [5, 5]));
+        let b = regions.get(regions.label(&[3, 1]).unwrap()).unwrap();
+        assert_eq!(regions.area(b), 4);
+        assert_eq!(regions.perimeter(b), 8);
+        assert_eq!(regions.sides(b)?, 4);
+        assert_eq!(regions.holes(b), 0);
+        Ok(())
+    }
+
+    #[test]
+    pub fn connectivity() -> Result<(), Error> {
+        let board = parse_board("#.#\n.#.\n#.#\n");
+        let four = label_where(&board, Connectivity::Four, |v| *v == b'#')?;
+        assert_eq!(four.len(), 5);
+        assert_eq!(four.label(&[1, 0]), None);
+        let eight = label_where(&board, Connectivity::Eight, |v| *v == b'#')?;
+        assert_eq!(eight.len(), 1);
+        let region = eight.get(0).unwrap();
+        assert_eq!(eight.area(region), 5);
+        assert_eq!(eight.holes(region), 0);
+        // the cells only touch diagonally, there is no outline to count corners of
+        assert!(matches!(eight.corners(region), Err(Error::InvalidState(_))));
+        assert!(eight.sides(region).is_err());
+
+        let ring = parse_board("###\n#.#\n###\n");
+        let regions = label_where(&ring, Connectivity::Eight, |v| *v == b'#')?;
+        assert_eq!(regions.holes(regions.get(0).unwrap()), 1);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::space::{IterMutSpace, IterSpace, Space};
+use crate::space::{Dimension, IterMutSpace, IterSpace, Pos, Space, SpaceMut};
 use bit_set::BitSet;
 use bit_vec::BitBlock;
 use derive_more::{Deref, DerefMut, Display};
@@ -32,6 +32,13 @@ impl<T, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[T]>> Space<T, usize, 2> for Board2d<
         let [x, y] = idx;
         self.inner.borrow().get(*y).and_then(|v| v.borrow().get(*x))
     }
+}
+
+impl<T, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[T]>> SpaceMut<T, usize, 2> for Board2d<T, Vy, Vx> {
+    type RefMut<'a>
+        = &'a mut T
+    where
+        Self: 'a;
 
     fn set(&mut self, idx: &[usize; 2], val: T) -> Option<T> {
         let [x, y] = idx;
@@ -49,6 +56,10 @@ impl<T, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[T]>> Space<T, usize, 2> for Board2d<
             .get_mut(*y)
             .and_then(|v| v.borrow_mut().get_mut(*x))
     }
+
+    fn swap(&mut self, idx1: &[usize; 2], idx2: &[usize; 2]) -> bool {
+        swap_in_rows(self.inner.borrow_mut(), idx1, idx2, |v| v.borrow_mut())
+    }
 }
 
 impl<T> Space<T, usize, 2> for RefBoard2d<'_, T>
@@ -59,6 +70,16 @@ where
         let [x, y] = idx;
         self.inner.get(*y).and_then(|v| v.get(*x))
     }
+}
+
+impl<T> SpaceMut<T, usize, 2> for RefBoard2d<'_, T>
+where
+    [T]: ToOwned<Owned = Vec<T>>,
+{
+    type RefMut<'a>
+        = &'a mut T
+    where
+        Self: 'a;
 
     fn set(&mut self, idx: &[usize; 2], val: T) -> Option<T> {
         let [x, y] = idx;
@@ -72,6 +93,64 @@ where
         let [x, y] = idx;
         self.inner.get_mut(*y).and_then(|v| v.to_mut().get_mut(*x))
     }
+
+    fn swap(&mut self, idx1: &[usize; 2], idx2: &[usize; 2]) -> bool {
+        swap_in_rows(&mut self.inner, idx1, idx2, |v| v.to_mut().as_mut_slice())
+    }
+}
+
+fn swap_in_rows<T, R, F: Fn(&mut R) -> &mut [T]>(
+    rows: &mut [R],
+    idx1: &[usize; 2],
+    idx2: &[usize; 2],
+    row_mut: F,
+) -> bool {
+    let ([x1, y1], [x2, y2]) = (*idx1, *idx2);
+    if y1 == y2 {
+        return match rows.get_mut(y1).map(row_mut) {
+            Some(row) if x1 < row.len() && x2 < row.len() => {
+                row.swap(x1, x2);
+                true
+            }
+            _ => false,
+        };
+    }
+
+    let ([x_low, y_low], [x_high, y_high]) = if y1 < y2 {
+        ([x1, y1], [x2, y2])
+    } else {
+        ([x2, y2], [x1, y1])
+    };
+    if y_high >= rows.len() {
+        return false;
+    }
+    let (low_rows, high_rows) = rows.split_at_mut(y_high);
+    match (
+        row_mut(&mut low_rows[y_low]).get_mut(x_low),
+        row_mut(&mut high_rows[0]).get_mut(x_high),
+    ) {
+        (Some(val1), Some(val2)) => {
+            std::mem::swap(val1, val2);
+            true
+        }
+        _ => false,
+    }
+}
+
+impl<T, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[T]>> Dimension<2> for Board2d<T, Vy, Vx> {
+    fn dimension(&self) -> [usize; 2] {
+        let rows = self.inner.borrow();
+        [rows.first().map_or(0, |v| v.borrow().len()), rows.len()]
+    }
+}
+
+impl<T> Dimension<2> for RefBoard2d<'_, T>
+where
+    [T]: ToOwned<Owned = Vec<T>>,
+{
+    fn dimension(&self) -> [usize; 2] {
+        [self.inner.first().map_or(0, |v| v.len()), self.inner.len()]
+    }
 }
 
 impl<T, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[T]>> IterSpace<T, usize, 2> for Board2d<T, Vy, Vx> {
@@ -207,6 +286,46 @@ impl<B: BitBlock> BitBoard2d<B> {
     }
 }
 
+/// Cells past the allocated bits of a row are not visited, they are all `false`.
+impl<B: BitBlock> IterSpace<bool, usize, 2> for BitBoard2d<B> {
+    fn iter(&self) -> impl Iterator<Item = ([usize; 2], &bool)> {
+        self.inner.iter().enumerate().flat_map(|(y, row)| {
+            (0..row.get_ref().len())
+                .map(move |x| ([x, y], if row.contains(x) { &true } else { &false }))
+        })
+    }
+}
+
+/// Write-back proxy for a single bit of a [`BitBoard2d`], the bit is stored on drop.
+pub struct BitRefMut<'a, B: BitBlock> {
+    row: &'a mut BitSet<B>,
+    x: usize,
+    val: bool,
+}
+
+impl<B: BitBlock> Deref for BitRefMut<'_, B> {
+    type Target = bool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl<B: BitBlock> DerefMut for BitRefMut<'_, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.val
+    }
+}
+
+impl<B: BitBlock> Drop for BitRefMut<'_, B> {
+    fn drop(&mut self) {
+        match self.val {
+            true => self.row.insert(self.x),
+            false => self.row.remove(self.x),
+        };
+    }
+}
+
 impl<B: BitBlock> Space<bool, usize, 2> for BitBoard2d<B> {
     fn get(&self, idx: &[usize; 2]) -> Option<&bool> {
         let [x, y] = idx;
@@ -214,6 +333,13 @@ impl<B: BitBlock> Space<bool, usize, 2> for BitBoard2d<B> {
             .get(*y)
             .map(|v| if v.contains(*x) { &true } else { &false })
     }
+}
+
+impl<B: BitBlock> SpaceMut<bool, usize, 2> for BitBoard2d<B> {
+    type RefMut<'a>
+        = BitRefMut<'a, B>
+    where
+        Self: 'a;
 
     fn set(&mut self, idx: &[usize; 2], val: bool) -> Option<bool> {
         let [x, y] = idx;
@@ -223,8 +349,23 @@ impl<B: BitBlock> Space<bool, usize, 2> for BitBoard2d<B> {
         })
     }
 
-    fn get_mut(&mut self, _idx: &[usize; 2]) -> Option<&mut bool> {
-        unimplemented!()
+    fn get_mut(&mut self, idx: &[usize; 2]) -> Option<BitRefMut<'_, B>> {
+        let [x, y] = idx;
+        self.inner.get_mut(*y).map(|row| {
+            let val = row.contains(*x);
+            BitRefMut { row, x: *x, val }
+        })
+    }
+
+    fn swap(&mut self, idx1: &[usize; 2], idx2: &[usize; 2]) -> bool {
+        match (self.get(idx1).copied(), self.get(idx2).copied()) {
+            (Some(val1), Some(val2)) => {
+                self.set(idx1, val2);
+                self.set(idx2, val1);
+                true
+            }
+            _ => false,
+        }
     }
 }
 
@@ -240,6 +381,75 @@ impl<B: BitBlock> BitBoard2d<B> {
             false => v.remove(*x),
         })
     }
+
+    pub fn count_ones(&self) -> usize {
+        self.inner.iter().map(BitSet::len).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.iter_mut().for_each(BitSet::clear);
+    }
+
+    pub fn iter_row(&self, y: usize) -> impl Iterator<Item = usize> + '_ {
+        self.inner.get(y).into_iter().flat_map(BitSet::iter)
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = [usize; 2]> + '_ {
+        self.inner
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().map(move |x| [x, y]))
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if self.inner.len() < other.inner.len() {
+            self.inner.resize_with(other.inner.len(), BitSet::default);
+        }
+        self.inner
+            .iter_mut()
+            .zip(other.inner.iter())
+            .for_each(|(row, other_row)| row.union_with(other_row));
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.inner
+            .iter_mut()
+            .enumerate()
+            .for_each(|(y, row)| match other.inner.get(y) {
+                Some(other_row) => row.intersect_with(other_row),
+                None => row.clear(),
+            });
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.inner
+            .iter_mut()
+            .zip(other.inner.iter())
+            .for_each(|(row, other_row)| row.difference_with(other_row));
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        if self.inner.len() < other.inner.len() {
+            self.inner.resize_with(other.inner.len(), BitSet::default);
+        }
+        self.inner
+            .iter_mut()
+            .zip(other.inner.iter())
+            .for_each(|(row, other_row)| row.symmetric_difference_with(other_row));
+    }
+
+    /// Moves every set bit by `diff`, bits shifted to a negative coordinate are dropped.
+    pub fn shift(&self, diff: &[isize; 2]) -> Self {
+        let [_, dy] = *diff;
+        let height = self.inner.len().saturating_add_signed(dy);
+        let mut res = Self::with_height(height);
+        self.iter_ones()
+            .filter_map(|pos| pos.shift(diff))
+            .for_each(|[x, y]| {
+                res.inner[y].insert(x);
+            });
+        res
+    }
 }
 
 #[allow(dead_code)]
@@ -309,3 +519,247 @@ impl Direction {
         &[Self::West, Self::North, Self::South, Self::East]
     }
 }
+
+#[derive(Eq, PartialEq, Hash, Debug, Display, Clone, Copy)]
+pub enum Direction8 {
+    West,
+    NorthWest,
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+}
+
+#[allow(dead_code)]
+impl Direction8 {
+    pub const fn get_movement_vec(&self) -> &'static [isize; 2] {
+        match self {
+            Direction8::West => &[-1, 0],
+            Direction8::NorthWest => &[-1, -1],
+            Direction8::North => &[0, -1],
+            Direction8::NorthEast => &[1, -1],
+            Direction8::East => &[1, 0],
+            Direction8::SouthEast => &[1, 1],
+            Direction8::South => &[0, 1],
+            Direction8::SouthWest => &[-1, 1],
+        }
+    }
+
+    pub const fn clockwise_45(&self) -> Self {
+        match self {
+            Direction8::West => Direction8::NorthWest,
+            Direction8::NorthWest => Direction8::North,
+            Direction8::North => Direction8::NorthEast,
+            Direction8::NorthEast => Direction8::East,
+            Direction8::East => Direction8::SouthEast,
+            Direction8::SouthEast => Direction8::South,
+            Direction8::South => Direction8::SouthWest,
+            Direction8::SouthWest => Direction8::West,
+        }
+    }
+
+    pub const fn counter_clockwise_45(&self) -> Self {
+        match self {
+            Direction8::West => Direction8::SouthWest,
+            Direction8::NorthWest => Direction8::West,
+            Direction8::North => Direction8::NorthWest,
+            Direction8::NorthEast => Direction8::North,
+            Direction8::East => Direction8::NorthEast,
+            Direction8::SouthEast => Direction8::East,
+            Direction8::South => Direction8::SouthEast,
+            Direction8::SouthWest => Direction8::South,
+        }
+    }
+
+    pub const fn clockwise_90(&self) -> Self {
+        self.clockwise_45().clockwise_45()
+    }
+
+    pub const fn counter_clockwise_90(&self) -> Self {
+        self.counter_clockwise_45().counter_clockwise_45()
+    }
+
+    pub const fn opposite(&self) -> Self {
+        self.clockwise_90().clockwise_90()
+    }
+
+    pub const fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            Direction8::NorthWest
+                | Direction8::NorthEast
+                | Direction8::SouthEast
+                | Direction8::SouthWest
+        )
+    }
+
+    pub const fn all() -> &'static [Direction8] {
+        &[
+            Self::West,
+            Self::NorthWest,
+            Self::North,
+            Self::NorthEast,
+            Self::East,
+            Self::SouthEast,
+            Self::South,
+            Self::SouthWest,
+        ]
+    }
+
+    pub const fn cardinal() -> &'static [Direction8] {
+        &[Self::West, Self::North, Self::East, Self::South]
+    }
+
+    pub const fn diagonal() -> &'static [Direction8] {
+        &[
+            Self::NorthWest,
+            Self::NorthEast,
+            Self::SouthEast,
+            Self::SouthWest,
+        ]
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    fn try_from(value: Direction8) -> Result<Self, Self::Error> {
+        match value {
+            Direction8::West => Ok(Direction::West),
+            Direction8::North => Ok(Direction::North),
+            Direction8::East => Ok(Direction::East),
+            Direction8::South => Ok(Direction::South),
+            d => Err(d),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::West => Direction8::West,
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::set::{BoolSpace, Set};
+    use crate::space::space2d::{BitBoard2d, Board2d, Direction, Direction8, RefBoard2d};
+    use crate::space::{Pos, Space, SpaceMut};
+    use crate::utils::{neighbors8, ray, ray_in_space};
+    use std::borrow::Cow;
+
+    #[test]
+    pub fn direction8_rotation() {
+        for direction in Direction8::all() {
+            assert_eq!(direction.clockwise_45().counter_clockwise_45(), *direction);
+            assert_eq!(direction.opposite().opposite(), *direction);
+            let [x, y] = direction.get_movement_vec();
+            let [ox, oy] = direction.opposite().get_movement_vec();
+            assert_eq!([x + ox, y + oy], [0, 0]);
+        }
+        for direction in Direction::cardinal() {
+            let direction8 = Direction8::from(*direction);
+            assert_eq!(
+                Direction::try_from(direction8.clockwise_90()),
+                Ok(direction.clockwise_90())
+            );
+        }
+        assert_eq!(
+            Direction::try_from(Direction8::NorthEast),
+            Err(Direction8::NorthEast)
+        );
+    }
+
+    #[test]
+    pub fn ray_stop_at_border() {
+        let board: RefBoard2d<u8> = vec![
+            Cow::Borrowed(b"abc".as_slice()),
+            Cow::Borrowed(b"def".as_slice()),
+            Cow::Borrowed(b"ghi".as_slice()),
+        ]
+        .into();
+        let diagonal = ray_in_space(&board, &[0, 0], Direction8::SouthEast.get_movement_vec())
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        assert_eq!(diagonal, b"ei");
+        assert_eq!(
+            ray_in_space(&board, &[0, 2], Direction8::West.get_movement_vec()).count(),
+            0
+        );
+        assert_eq!(ray_in_space(&board, &[1, 1], &[0, 0]).count(), 0);
+        assert_eq!(ray(&[1, 1], &[0, 0]).count(), 0);
+        assert_eq!(neighbors8(&[0, 0]).count(), 3);
+        assert_eq!(neighbors8(&[1, 1]).count(), 8);
+        assert_eq!(
+            [1, 1].shift(Direction8::NorthWest.get_movement_vec()),
+            Some([0, 0])
+        );
+    }
+
+    #[test]
+    pub fn swap() {
+        let mut board = Board2d::from(vec![b"ab".to_vec(), b"cd".to_vec()]);
+        assert!(board.swap(&[0, 0], &[1, 1]));
+        assert!(board.swap(&[0, 1], &[1, 1]));
+        assert!(!board.swap(&[0, 0], &[2, 0]));
+        assert_eq!(board.as_ref(), &[b"db".to_vec(), b"ac".to_vec()]);
+    }
+
+    #[test]
+    pub fn bit_board_mut() -> Result<(), Error> {
+        let mut board = BitBoard2d::<usize>::with_height(2);
+        *board.get_mut(&[3, 1]).unwrap() = true;
+        assert_eq!(board.get(&[3, 1]), Some(&true));
+        assert!(board.get_mut(&[0, 2]).is_none());
+        assert!(board.swap(&[3, 1], &[0, 0]));
+        assert_eq!(board.iter_ones().collect::<Vec<_>>(), [[0, 0]]);
+
+        let mut visited = BoolSpace::from(board);
+        assert!(visited.insert([5, 1])?);
+        assert!(!visited.insert([5, 1])?);
+        assert!(visited.contains(&[0, 0])?);
+        assert!(visited.insert([9, 9]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn bit_board_bulk() {
+        let mut a = BitBoard2d::<usize>::with_height(2);
+        a.set(&[0, 0], true);
+        a.set(&[1, 1], true);
+        let mut b = BitBoard2d::<usize>::with_height(3);
+        b.set(&[1, 1], true);
+        b.set(&[2, 2], true);
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.count_ones(), 3);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection.iter_ones().collect::<Vec<_>>(), [[1, 1]]);
+        let mut difference = a.clone();
+        difference.difference_with(&b);
+        assert_eq!(difference.iter_ones().collect::<Vec<_>>(), [[0, 0]]);
+        let mut symmetric_difference = a.clone();
+        symmetric_difference.symmetric_difference_with(&b);
+        assert_eq!(
+            symmetric_difference.iter_ones().collect::<Vec<_>>(),
+            [[0, 0], [2, 2]]
+        );
+
+        let shifted = union.shift(&[-1, 1]);
+        assert_eq!(shifted.iter_ones().collect::<Vec<_>>(), [[0, 2], [1, 3]]);
+        assert_eq!(shifted.iter_row(3).collect::<Vec<_>>(), [1]);
+        let mut cleared = shifted;
+        cleared.clear();
+        assert_eq!(cleared.count_ones(), 0);
+    }
+}
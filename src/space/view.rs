@@ -0,0 +1,339 @@
+use crate::space::{Dimension, IterSpace, Space, SpaceMut};
+use derive_more::From;
+use std::ops::{Deref, DerefMut};
+
+// Views wrap anything dereferencing to a space, e.g. `&Board2d<T>`, `&mut Board2d<T>`,
+// `Box<Board2d<T>>` or `&mut Rotated<..>` to stack transforms, so the underlying cells are never
+// copied. Writing through a view needs the wrapped space to be mutable.
+#[derive(Clone, Debug, From)]
+pub struct Transposed<S> {
+    inner: S,
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum Rotation {
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rotated<S> {
+    inner: S,
+    rotation: Rotation,
+    inner_dimension: [usize; 2],
+}
+
+/// `Horizontal` mirrors left to right, `Vertical` mirrors top to bottom.
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum Flip {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Debug)]
+pub struct Flipped<S> {
+    inner: S,
+    flip: Flip,
+    inner_dimension: [usize; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct Window<S> {
+    inner: S,
+    origin: [usize; 2],
+    dimension: [usize; 2],
+}
+
+#[allow(dead_code)]
+impl<S> Transposed<S> {
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[allow(dead_code)]
+impl<S: Deref<Target: Dimension<2>>> Rotated<S> {
+    pub fn new(inner: S, rotation: Rotation) -> Self {
+        let inner_dimension = inner.dimension();
+        Self {
+            inner,
+            rotation,
+            inner_dimension,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[allow(dead_code)]
+impl<S: Deref<Target: Dimension<2>>> Flipped<S> {
+    pub fn new(inner: S, flip: Flip) -> Self {
+        let inner_dimension = inner.dimension();
+        Self {
+            inner,
+            flip,
+            inner_dimension,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[allow(dead_code)]
+impl<S> Window<S> {
+    pub fn new(inner: S, origin: [usize; 2], dimension: [usize; 2]) -> Self {
+        Self {
+            inner,
+            origin,
+            dimension,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+trait ToInner {
+    fn to_inner(&self, idx: &[usize; 2]) -> Option<[usize; 2]>;
+}
+
+impl<S> ToInner for Transposed<S> {
+    fn to_inner(&self, idx: &[usize; 2]) -> Option<[usize; 2]> {
+        let [x, y] = *idx;
+        Some([y, x])
+    }
+}
+
+impl<S> ToInner for Rotated<S> {
+    fn to_inner(&self, idx: &[usize; 2]) -> Option<[usize; 2]> {
+        let [x, y] = *idx;
+        let [width, height] = self.inner_dimension;
+        match self.rotation {
+            Rotation::Clockwise90 => Some([y, height.checked_sub(x)?.checked_sub(1)?]),
+            Rotation::Clockwise180 => Some([
+                width.checked_sub(x)?.checked_sub(1)?,
+                height.checked_sub(y)?.checked_sub(1)?,
+            ]),
+            Rotation::Clockwise270 => Some([width.checked_sub(y)?.checked_sub(1)?, x]),
+        }
+    }
+}
+
+impl<S> ToInner for Flipped<S> {
+    fn to_inner(&self, idx: &[usize; 2]) -> Option<[usize; 2]> {
+        let [x, y] = *idx;
+        let [width, height] = self.inner_dimension;
+        match self.flip {
+            Flip::Horizontal => Some([width.checked_sub(x)?.checked_sub(1)?, y]),
+            Flip::Vertical => Some([x, height.checked_sub(y)?.checked_sub(1)?]),
+        }
+    }
+}
+
+impl<S> ToInner for Window<S> {
+    fn to_inner(&self, idx: &[usize; 2]) -> Option<[usize; 2]> {
+        let [x, y] = *idx;
+        let [width, height] = self.dimension;
+        let [origin_x, origin_y] = self.origin;
+        if x < width && y < height {
+            Some([origin_x.checked_add(x)?, origin_y.checked_add(y)?])
+        } else {
+            None
+        }
+    }
+}
+
+impl<S: Deref<Target: Dimension<2>>> Dimension<2> for Transposed<S> {
+    fn dimension(&self) -> [usize; 2] {
+        let [width, height] = self.inner.dimension();
+        [height, width]
+    }
+}
+
+impl<S> Dimension<2> for Rotated<S> {
+    fn dimension(&self) -> [usize; 2] {
+        let [width, height] = self.inner_dimension;
+        match self.rotation {
+            Rotation::Clockwise180 => [width, height],
+            Rotation::Clockwise90 | Rotation::Clockwise270 => [height, width],
+        }
+    }
+}
+
+impl<S> Dimension<2> for Flipped<S> {
+    fn dimension(&self) -> [usize; 2] {
+        self.inner_dimension
+    }
+}
+
+impl<S> Dimension<2> for Window<S> {
+    fn dimension(&self) -> [usize; 2] {
+        self.dimension
+    }
+}
+
+macro_rules! view_space {
+    ($view:ident) => {
+        impl<T, S> Space<T, usize, 2> for $view<S>
+        where
+            S: Deref<Target: Space<T, usize, 2>>,
+        {
+            fn get(&self, idx: &[usize; 2]) -> Option<&T> {
+                self.to_inner(idx).and_then(|idx| self.inner.get(&idx))
+            }
+        }
+
+        impl<T, S> SpaceMut<T, usize, 2> for $view<S>
+        where
+            S: DerefMut<Target: SpaceMut<T, usize, 2>>,
+        {
+            type RefMut<'a>
+                = <S::Target as SpaceMut<T, usize, 2>>::RefMut<'a>
+            where
+                Self: 'a;
+
+            fn set(&mut self, idx: &[usize; 2], val: T) -> Option<T> {
+                self.to_inner(idx).and_then(|idx| self.inner.set(&idx, val))
+            }
+
+            fn get_mut(&mut self, idx: &[usize; 2]) -> Option<Self::RefMut<'_>> {
+                self.to_inner(idx).and_then(|idx| self.inner.get_mut(&idx))
+            }
+
+            fn swap(&mut self, idx1: &[usize; 2], idx2: &[usize; 2]) -> bool {
+                match (self.to_inner(idx1), self.to_inner(idx2)) {
+                    (Some(idx1), Some(idx2)) => self.inner.swap(&idx1, &idx2),
+                    _ => false,
+                }
+            }
+        }
+
+        impl<T, S> IterSpace<T, usize, 2> for $view<S>
+        where
+            S: Deref<Target: Space<T, usize, 2>>,
+            $view<S>: Dimension<2>,
+        {
+            fn iter(&self) -> impl Iterator<Item = ([usize; 2], &T)>
+            where
+                T: 'static,
+            {
+                let [width, height] = self.dimension();
+                (0..height).flat_map(move |y| {
+                    (0..width).filter_map(move |x| self.get(&[x, y]).map(|val| ([x, y], val)))
+                })
+            }
+        }
+    };
+}
+
+view_space!(Transposed);
+view_space!(Rotated);
+view_space!(Flipped);
+view_space!(Window);
+
+#[allow(dead_code)]
+pub fn row<'a, T: 'a, S: Space<T, usize, 2> + Dimension<2>>(
+    space: &'a S,
+    y: usize,
+) -> impl Iterator<Item = &'a T> {
+    let [width, _] = space.dimension();
+    (0..width).map_while(move |x| space.get(&[x, y]))
+}
+
+#[allow(dead_code)]
+pub fn column<'a, T: 'a, S: Space<T, usize, 2> + Dimension<2>>(
+    space: &'a S,
+    x: usize,
+) -> impl Iterator<Item = &'a T> {
+    let [_, height] = space.dimension();
+    (0..height).map_while(move |y| space.get(&[x, y]))
+}
+
+#[allow(dead_code)]
+pub fn rows<'a, T: 'a, S: Space<T, usize, 2> + Dimension<2>>(
+    space: &'a S,
+) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+    let [_, height] = space.dimension();
+    (0..height).map(move |y| row(space, y))
+}
+
+#[allow(dead_code)]
+pub fn columns<'a, T: 'a, S: Space<T, usize, 2> + Dimension<2>>(
+    space: &'a S,
+) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+    let [width, _] = space.dimension();
+    (0..width).map(move |x| column(space, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::space::space2d::Board2d;
+    use crate::space::view::{
+        column, columns, rows, Flip, Flipped, Rotated, Rotation, Transposed, Window,
+    };
+    use crate::space::{Dimension, IterSpace, Space, SpaceMut};
+
+    fn board() -> Board2d<u8> {
+        Board2d::from(vec![b"abc".to_vec(), b"def".to_vec()])
+    }
+
+    fn collect_rows<S: Space<u8, usize, 2> + Dimension<2>>(space: &S) -> Vec<Vec<u8>> {
+        rows(space).map(|row| row.copied().collect()).collect()
+    }
+
+    #[test]
+    pub fn transform() {
+        let board = board();
+        assert_eq!(
+            collect_rows(&Transposed::from(&board)),
+            [b"ad".to_vec(), b"be".to_vec(), b"cf".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Rotated::new(&board, Rotation::Clockwise90)),
+            [b"da".to_vec(), b"eb".to_vec(), b"fc".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Rotated::new(&board, Rotation::Clockwise180)),
+            [b"fed".to_vec(), b"cba".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Rotated::new(&board, Rotation::Clockwise270)),
+            [b"cf".to_vec(), b"be".to_vec(), b"ad".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Flipped::new(&board, Flip::Horizontal)),
+            [b"cba".to_vec(), b"fed".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Flipped::new(&board, Flip::Vertical)),
+            [b"def".to_vec(), b"abc".to_vec()]
+        );
+        assert_eq!(
+            collect_rows(&Window::new(&board, [1, 0], [2, 2])),
+            [b"bc".to_vec(), b"ef".to_vec()]
+        );
+        assert_eq!(column(&board, 2).copied().collect::<Vec<_>>(), b"cf");
+        assert_eq!(columns(&board).count(), 3);
+    }
+
+    #[test]
+    pub fn stacked_write_through() {
+        let mut board = board();
+        let mut rotated = Rotated::new(&mut board, Rotation::Clockwise90);
+        let mut window = Window::new(&mut rotated, [1, 1], [1, 2]);
+        assert_eq!(window.set(&[0, 0], b'x'), Some(b'b'));
+        assert_eq!(window.get(&[0, 2]), None);
+        assert_eq!(
+            window.iter().map(|(pos, v)| (pos, *v)).collect::<Vec<_>>(),
+            [([0, 0], b'x'), ([0, 1], b'c')]
+        );
+        assert_eq!(board.get(&[1, 0]), Some(&b'x'));
+    }
+}
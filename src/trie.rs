@@ -1,10 +1,17 @@
 use crate::error::Error;
+use crate::hash::FastMap;
 use std::borrow::Borrow;
-use std::iter::Peekable;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::iter::{from_fn, Peekable};
 
+#[allow(dead_code)]
 pub trait TrieNode<T>: Default {
     fn get<B: Borrow<T>>(&self, t: B) -> Result<Option<&Self>, Error>;
+    fn get_mut<B: Borrow<T>>(&mut self, t: B) -> Result<Option<&mut Self>, Error>;
     fn get_mut_or_add_default<B: Borrow<T>>(&mut self, t: B) -> Result<&mut Self, Error>;
+    fn remove_child<B: Borrow<T>>(&mut self, t: B) -> Result<Option<Self>, Error>;
+    fn children(&self) -> impl Iterator<Item = &Self>;
     fn depth(&self) -> usize;
     fn is_valid(&self) -> bool;
     fn set_valid(&mut self, b: bool);
@@ -38,6 +45,66 @@ pub trait TrieNode<T>: Default {
         }
         Ok(None)
     }
+
+    /// Every valid node along `iter`, from the shortest prefix to the longest.
+    fn prefixes<Item: Borrow<T>, I: IntoIterator<Item = Item>>(
+        &self,
+        iter: I,
+    ) -> impl Iterator<Item = Result<&Self, Error>> {
+        let mut iter = iter.into_iter();
+        let mut current = Some(self);
+        from_fn(move || loop {
+            let node = current?;
+            match node.get(iter.next()?.borrow()) {
+                Err(e) => {
+                    current = None;
+                    return Some(Err(e));
+                }
+                Ok(next_node) => {
+                    current = next_node;
+                    if let Some(next_node) = next_node.filter(|n| n.is_valid()) {
+                        return Some(Ok(next_node));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Unmarks the word, pruning the nodes left without words. Returns `false` when the word
+    /// was not in the trie.
+    fn remove<B: Borrow<T>, I: IntoIterator<Item = B>>(&mut self, iter: I) -> Result<bool, Error> {
+        let mut iter = iter.into_iter();
+        match iter.next() {
+            None => {
+                let removed = self.is_valid();
+                self.set_valid(false);
+                Ok(removed)
+            }
+            Some(val) => {
+                let Some(child) = self.get_mut(val.borrow())? else {
+                    return Ok(false);
+                };
+                let removed = child.remove(iter)?;
+                if removed && !child.is_valid() && child.children().next().is_none() {
+                    self.remove_child(val)?;
+                }
+                Ok(removed)
+            }
+        }
+    }
+
+    /// Number of words ending at or below this node.
+    fn word_count(&self) -> usize {
+        let mut count = 0;
+        let mut work = vec![self];
+        while let Some(node) = work.pop() {
+            if node.is_valid() {
+                count += 1;
+            }
+            work.extend(node.children());
+        }
+        count
+    }
 }
 
 #[derive(Debug)]
@@ -74,6 +141,19 @@ impl<const N: usize> TrieNode<usize> for ArrayTrie<N> {
         Ok(res)
     }
 
+    fn get_mut<B: Borrow<usize>>(&mut self, t: B) -> Result<Option<&mut Self>, Error> {
+        let idx = *t.borrow();
+        let res = self
+            .children
+            .get_mut(idx)
+            .ok_or_else(|| {
+                Error::InvalidState(format!("out of bound, index {} bound {}", idx, N).into())
+            })?
+            .as_mut()
+            .map(|b| b.as_mut());
+        Ok(res)
+    }
+
     fn get_mut_or_add_default<B: Borrow<usize>>(&mut self, t: B) -> Result<&mut Self, Error> {
         let idx = *t.borrow();
         let res = self
@@ -93,6 +173,23 @@ impl<const N: usize> TrieNode<usize> for ArrayTrie<N> {
         Ok(res)
     }
 
+    fn remove_child<B: Borrow<usize>>(&mut self, t: B) -> Result<Option<Self>, Error> {
+        let idx = *t.borrow();
+        let res = self
+            .children
+            .get_mut(idx)
+            .ok_or_else(|| {
+                Error::InvalidState(format!("out of bound, index {} bound {}", idx, N).into())
+            })?
+            .take()
+            .map(|b| *b);
+        Ok(res)
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().flatten().map(|b| b.as_ref())
+    }
+
     fn depth(&self) -> usize {
         self.len
     }
@@ -105,3 +202,482 @@ impl<const N: usize> TrieNode<usize> for ArrayTrie<N> {
         self.valid = b;
     }
 }
+
+/// Trie over any hashable symbol, children are kept in a map instead of an array.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct HashTrie<T> {
+    children: FastMap<T, HashTrie<T>>,
+    depth: usize,
+    valid: bool,
+}
+
+impl<T> Default for HashTrie<T> {
+    fn default() -> Self {
+        Self {
+            children: FastMap::default(),
+            depth: 0,
+            valid: false,
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> TrieNode<T> for HashTrie<T> {
+    fn get<B: Borrow<T>>(&self, t: B) -> Result<Option<&Self>, Error> {
+        Ok(self.children.get(t.borrow()))
+    }
+
+    fn get_mut<B: Borrow<T>>(&mut self, t: B) -> Result<Option<&mut Self>, Error> {
+        Ok(self.children.get_mut(t.borrow()))
+    }
+
+    fn get_mut_or_add_default<B: Borrow<T>>(&mut self, t: B) -> Result<&mut Self, Error> {
+        let depth = self.depth + 1;
+        Ok(self
+            .children
+            .entry(t.borrow().clone())
+            .or_insert_with(|| Self {
+                depth,
+                ..Default::default()
+            }))
+    }
+
+    fn remove_child<B: Borrow<T>>(&mut self, t: B) -> Result<Option<Self>, Error> {
+        Ok(self.children.remove(t.borrow()))
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Self> {
+        self.children.values()
+    }
+
+    fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn set_valid(&mut self, b: bool) {
+        self.valid = b;
+    }
+}
+
+/// Trie over `0..N`, `N` at most 2^16, keeping the children of a node sorted by symbol in one
+/// exactly sized slice instead of [`ArrayTrie`]'s `N` boxed slots, so there are no empty slots
+/// and only nodes with children allocate. Nodes are not shared in a single arena indexed by
+/// `u32` since [`TrieNode`] walks through `&Self`, which such an arena cannot hand out.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct CompactTrie<const N: usize> {
+    children: Box<[CompactTrie<N>]>,
+    depth: u32,
+    symbol: u16,
+    valid: bool,
+}
+
+impl<const N: usize> CompactTrie<N> {
+    /// Position of the child `idx` in the sorted children, or where it would be inserted.
+    fn position(&self, idx: usize) -> Result<Result<usize, usize>, Error> {
+        let symbol = u16::try_from(idx).ok().filter(|_| idx < N).ok_or_else(|| {
+            Error::InvalidState(format!("out of bound, index {} bound {}", idx, N).into())
+        })?;
+        Ok(self
+            .children
+            .binary_search_by_key(&symbol, |child| child.symbol))
+    }
+}
+
+impl<const N: usize> TrieNode<usize> for CompactTrie<N> {
+    fn get<B: Borrow<usize>>(&self, t: B) -> Result<Option<&Self>, Error> {
+        Ok(self
+            .position(*t.borrow())?
+            .ok()
+            .map(|pos| &self.children[pos]))
+    }
+
+    fn get_mut<B: Borrow<usize>>(&mut self, t: B) -> Result<Option<&mut Self>, Error> {
+        Ok(self
+            .position(*t.borrow())?
+            .ok()
+            .map(|pos| &mut self.children[pos]))
+    }
+
+    fn get_mut_or_add_default<B: Borrow<usize>>(&mut self, t: B) -> Result<&mut Self, Error> {
+        let idx = *t.borrow();
+        let pos = match self.position(idx)? {
+            Ok(pos) => pos,
+            Err(pos) => {
+                let mut children = std::mem::take(&mut self.children).into_vec();
+                children.insert(
+                    pos,
+                    Self {
+                        depth: self.depth + 1,
+                        // position checked that idx fits in u16
+                        symbol: idx as u16,
+                        ..Default::default()
+                    },
+                );
+                self.children = children.into_boxed_slice();
+                pos
+            }
+        };
+        Ok(&mut self.children[pos])
+    }
+
+    fn remove_child<B: Borrow<usize>>(&mut self, t: B) -> Result<Option<Self>, Error> {
+        let Ok(pos) = self.position(*t.borrow())? else {
+            return Ok(None);
+        };
+        let mut children = std::mem::take(&mut self.children).into_vec();
+        let child = children.remove(pos);
+        self.children = children.into_boxed_slice();
+        Ok(Some(child))
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter()
+    }
+
+    fn depth(&self) -> usize {
+        self.depth as usize
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn set_valid(&mut self, b: bool) {
+        self.valid = b;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AhoCorasickNode<T> {
+    children: FastMap<T, usize>,
+    fail: usize,
+    /// Id of the word ending here, if any.
+    word: Option<usize>,
+    /// Closest node on the fail chain, excluding this one, where a word ends.
+    output: Option<usize>,
+}
+
+impl<T> Default for AhoCorasickNode<T> {
+    fn default() -> Self {
+        Self {
+            children: FastMap::default(),
+            fail: 0,
+            word: None,
+            output: None,
+        }
+    }
+}
+
+/// Occurrence of the word `word` (its position in the word list) at `start..end`.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub struct Match {
+    pub word: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Aho–Corasick automaton finding every occurrence of a set of words in a single scan.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<T> {
+    nodes: Vec<AhoCorasickNode<T>>,
+    word_lens: Vec<usize>,
+    word_count: usize,
+}
+
+#[allow(dead_code)]
+impl<T: Eq + Hash + Clone> AhoCorasick<T> {
+    /// Words are identified by their position in `words`, a duplicated word only matches under
+    /// its first id and the empty word never matches.
+    pub fn new<W: IntoIterator<Item = T>>(words: impl IntoIterator<Item = W>) -> Self {
+        let mut nodes = vec![AhoCorasickNode::default()];
+        let mut word_lens = Vec::new();
+        let mut word_count = 0;
+        for word in words {
+            let mut current = 0;
+            let mut len = 0;
+            for symbol in word {
+                current = match nodes[current].children.get(&symbol) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(AhoCorasickNode::default());
+                        let next = nodes.len() - 1;
+                        nodes[current].children.insert(symbol, next);
+                        next
+                    }
+                };
+                len += 1;
+            }
+            if current != 0 && nodes[current].word.is_none() {
+                nodes[current].word = Some(word_lens.len());
+                word_count += 1;
+            }
+            word_lens.push(len);
+        }
+
+        let mut work: VecDeque<_> = nodes[0].children.values().copied().collect();
+        while let Some(node) = work.pop_front() {
+            let children: Vec<_> = nodes[node]
+                .children
+                .iter()
+                .map(|(symbol, child)| (symbol.clone(), *child))
+                .collect();
+            for (symbol, child) in children {
+                let mut fail = nodes[node].fail;
+                let child_fail = loop {
+                    match nodes[fail].children.get(&symbol) {
+                        Some(&next) => break next,
+                        None if fail == 0 => break 0,
+                        None => fail = nodes[fail].fail,
+                    }
+                };
+                nodes[child].fail = child_fail;
+                nodes[child].output = if nodes[child_fail].word.is_some() {
+                    Some(child_fail)
+                } else {
+                    nodes[child_fail].output
+                };
+                work.push_back(child);
+            }
+        }
+        Self {
+            nodes,
+            word_lens,
+            word_count,
+        }
+    }
+
+    /// Distinct non-empty words.
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    fn step(&self, mut node: usize, symbol: &T) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].children.get(symbol) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// Every occurrence, overlapping ones included, ordered by their end.
+    pub fn find_iter<'a, B: Borrow<T>>(
+        &'a self,
+        haystack: impl IntoIterator<Item = B> + 'a,
+    ) -> impl Iterator<Item = Match> + 'a {
+        let mut node = 0;
+        haystack
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(pos, symbol)| {
+                node = self.step(node, symbol.borrow());
+                let first = if self.nodes[node].word.is_some() {
+                    Some(node)
+                } else {
+                    self.nodes[node].output
+                };
+                std::iter::successors(first, |found| self.nodes[*found].output).map(move |found| {
+                    let word = self.nodes[found].word.unwrap();
+                    Match {
+                        word,
+                        start: pos + 1 - self.word_lens[word],
+                        end: pos + 1,
+                    }
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::trie::{AhoCorasick, ArrayTrie, CompactTrie, HashTrie, Match, TrieNode};
+
+    #[test]
+    pub fn hash_trie() -> Result<(), Error> {
+        let mut trie = HashTrie::default();
+        for word in ["b", "br", "bwu", "r", "brwr"] {
+            trie.add(word.chars())?;
+        }
+        assert_eq!(trie.word_count(), 5);
+        let prefixes = trie
+            .prefixes("brwrr".chars())
+            .map(|node| node.map(|n| n.depth()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(prefixes, [1, 2, 4]);
+        assert_eq!(
+            trie.find_prefix(&mut "brwrr".chars().peekable())?
+                .map(|n| n.depth()),
+            Some(1)
+        );
+
+        assert!(trie.remove("brwr".chars())?);
+        assert!(!trie.remove("brwr".chars())?);
+        assert!(!trie.remove("bw".chars())?);
+        assert_eq!(trie.word_count(), 4);
+        assert!(trie
+            .get('b')?
+            .unwrap()
+            .get('r')?
+            .unwrap()
+            .get('w')?
+            .is_none());
+        assert!(trie.remove("b".chars())?);
+        assert!(trie.get('b')?.is_some());
+        assert_eq!(trie.prefixes("brwrr".chars()).count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn array_trie_remove() -> Result<(), Error> {
+        let mut trie = ArrayTrie::<3>::default();
+        trie.add([0, 1, 2])?;
+        trie.add([0])?;
+        assert!(trie.remove([0, 1, 2])?);
+        assert!(trie.get(0)?.unwrap().get(1)?.is_none());
+        assert_eq!(trie.word_count(), 1);
+        assert!(trie.remove([5]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn compact_trie() -> Result<(), Error> {
+        let words: [&[usize]; 4] = [&[0, 1], &[0, 1, 2, 2], &[2], &[0, 2, 1]];
+        let mut compact = CompactTrie::<3>::default();
+        let mut array = ArrayTrie::<3>::default();
+        for word in words {
+            compact.add(word.iter().copied())?;
+            array.add(word.iter().copied())?;
+        }
+        assert_eq!(compact.word_count(), array.word_count());
+
+        for input in [
+            [0, 1, 2, 2, 1],
+            [0, 2, 1, 0, 0],
+            [1, 0, 0, 0, 0],
+            [2, 2, 2, 2, 2],
+        ] {
+            let compact_depths = compact
+                .prefixes(input)
+                .map(|n| n.map(|n| n.depth()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let array_depths = array
+                .prefixes(input)
+                .map(|n| n.map(|n| n.depth()))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(compact_depths, array_depths);
+        }
+        assert!(compact.remove([0, 1, 2, 2])?);
+        assert!(compact.get(0)?.unwrap().get(1)?.unwrap().get(2)?.is_none());
+        assert_eq!(compact.word_count(), 3);
+        assert!(compact.add([3]).is_err());
+        assert!(compact.get(3).is_err());
+        assert!(compact.get(1)?.is_none());
+        Ok(())
+    }
+
+    /// Heap bytes and allocations of both tries on a dictionary shaped like day 19's, 450 towels
+    /// of 1 to 8 stripes in 5 colors.
+    #[test]
+    pub fn compact_trie_footprint() -> Result<(), Error> {
+        let mut seed = 19u64;
+        let mut next = move |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % bound) as usize
+        };
+        let mut compact = CompactTrie::<5>::default();
+        let mut array = ArrayTrie::<5>::default();
+        for _ in 0..450 {
+            let word = (0..1 + next(8)).map(|_| next(5)).collect::<Vec<_>>();
+            compact.add(word.iter().copied())?;
+            array.add(word)?;
+        }
+        assert_eq!(compact.word_count(), array.word_count());
+
+        let mut array_nodes = 0;
+        let mut work = vec![&array];
+        while let Some(node) = work.pop() {
+            array_nodes += 1;
+            work.extend(node.children());
+        }
+        // every node but the root is its own box
+        let array_allocs = array_nodes - 1;
+        let array_bytes = array_allocs * size_of::<ArrayTrie<5>>();
+
+        let (mut compact_allocs, mut compact_bytes) = (0, 0);
+        let mut work = vec![&compact];
+        while let Some(node) = work.pop() {
+            if !node.children.is_empty() {
+                compact_allocs += 1;
+                compact_bytes += node.children.len() * size_of::<CompactTrie<5>>();
+            }
+            work.extend(node.children());
+        }
+        assert!(compact_allocs < array_allocs);
+        assert!(compact_bytes * 2 < array_bytes);
+        Ok(())
+    }
+
+    #[test]
+    pub fn aho_corasick() {
+        let words = ["he", "she", "his", "hers", "e"];
+        let automaton = AhoCorasick::new(words.iter().map(|w| w.chars()));
+        let haystack = "ushers and his";
+        let mut matches = automaton.find_iter(haystack.chars()).collect::<Vec<_>>();
+        let mut expected = Vec::new();
+        for (word, pattern) in words.iter().enumerate() {
+            for start in 0..haystack.len() {
+                if haystack[start..].starts_with(pattern) {
+                    expected.push(Match {
+                        word,
+                        start,
+                        end: start + pattern.len(),
+                    });
+                }
+            }
+        }
+        matches.sort_by_key(|m| (m.word, m.start));
+        assert_eq!(matches, expected);
+        assert_eq!(automaton.word_count(), 5);
+    }
+
+    #[test]
+    pub fn aho_corasick_duplicate_words() {
+        let automaton = AhoCorasick::new(["ab", "b", "ab"].iter().map(|w| w.chars()));
+        let mut matches = automaton.find_iter("abab".chars()).collect::<Vec<_>>();
+        matches.sort_by_key(|m| (m.word, m.start));
+        let expected = [(0, 0, 2), (0, 2, 4), (1, 1, 2), (1, 3, 4)]
+            .map(|(word, start, end)| Match { word, start, end });
+        assert_eq!(matches, expected);
+        assert_eq!(automaton.word_count(), 2);
+    }
+
+    #[test]
+    pub fn aho_corasick_empty_word() {
+        let automaton = AhoCorasick::new(["", "b"].iter().map(|w| w.chars()));
+        let matches = automaton.find_iter("abc".chars()).collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            [Match {
+                word: 1,
+                start: 1,
+                end: 2
+            }]
+        );
+        assert_eq!(automaton.word_count(), 1);
+
+        let automaton = AhoCorasick::new([""].iter().map(|w| w.chars()));
+        assert_eq!(automaton.find_iter("abc".chars()).count(), 0);
+        assert_eq!(automaton.word_count(), 0);
+    }
+}
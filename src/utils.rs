@@ -1,5 +1,7 @@
 use crate::error::Error;
-use crate::space::Pos;
+use crate::hash::FastHashState;
+use crate::space::space2d::Direction8;
+use crate::space::{Pos, Space};
 use crate::y2024;
 use chrono::{Datelike, Utc};
 use chrono_tz::US::Eastern;
@@ -10,7 +12,10 @@ use scraper::{Html, Selector};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::fs;
+use std::iter;
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, LazyLock, OnceLock};
 use std::time::Duration;
@@ -137,11 +142,42 @@ pub enum UtilsError {
     RegexError(Cow<'static, str>),
     #[error("invalid aoc problem: `{0}`")]
     InvalidAOCProblem(Cow<'static, str>),
+    #[error("error dumping graph: `{0}`")]
+    DumpGraphError(Cow<'static, str>),
+}
+
+static DUMP_GRAPH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Enables [`dump_graph`], every dump is written to `<dir>/<name>.dot`.
+pub fn set_dump_graph_dir(dir: PathBuf) -> Result<(), UtilsError> {
+    fs::create_dir_all(&dir).map_err(|e| {
+        UtilsError::DumpGraphError(format!("failed to create dir {:?}: {}", dir, e).into())
+    })?;
+    DUMP_GRAPH_DIR
+        .set(dir)
+        .map_err(|dir| UtilsError::DumpGraphError(format!("already set to {:?}", dir).into()))
+}
+
+/// Writes the DOT output of `dot` when `--dump-graph` is given, `dot` is not called otherwise.
+pub fn dump_graph(name: &str, dot: impl FnOnce() -> String) -> Result<(), UtilsError> {
+    if let Some(dir) = DUMP_GRAPH_DIR.get() {
+        let path = dir.join(format!("{}.dot", name));
+        fs::write(&path, dot()).map_err(|e| {
+            UtilsError::DumpGraphError(format!("failed to write {:?}: {}", path, e).into())
+        })?;
+    }
+    Ok(())
+}
+
+/// Whether `--dump-graph` is given, for the dumps that need recording while solving.
+pub fn dump_graph_enabled() -> bool {
+    DUMP_GRAPH_DIR.get().is_some()
 }
 
 pub fn get_input(year: u16, day: u8, session: &str) -> Result<Arc<String>, UtilsError> {
     static REQWEST_CLIENT: LazyLock<Client> = LazyLock::new(default_reqwest_client);
-    static MEM_CACHE: LazyLock<DashMap<String, Arc<String>>> = LazyLock::new(DashMap::new);
+    static MEM_CACHE: LazyLock<DashMap<String, Arc<String>, FastHashState>> =
+        LazyLock::new(DashMap::default);
 
     let key = Rc::new(format!("{}_{}_{}", year, day, session));
     let mem_cache_map = &*MEM_CACHE;
@@ -316,11 +352,43 @@ pub fn check_valid_question(year: u16, day: Option<u8>) -> Result<RangeInclusive
 }
 
 pub fn cardinal(pos: &[usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<'_> {
-    static DIRECTION: &[[isize; 2]; 4] = &[[-1, 0], [0, -1], [1, 0], [0, 1]];
+    Direction8::cardinal()
+        .iter()
+        .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+}
+
+#[allow(dead_code)]
+pub fn diagonal(pos: &[usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<'_> {
+    Direction8::diagonal()
+        .iter()
+        .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+}
 
-    DIRECTION
+#[allow(dead_code)]
+pub fn neighbors8(pos: &[usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<'_> {
+    Direction8::all()
         .iter()
-        .filter_map(|direction| pos.shift(direction))
+        .filter_map(|direction| pos.shift(direction.get_movement_vec()))
+}
+
+/// Walks from `pos` (exclusive) by repeatedly shifting with `diff`, stopping once a coordinate
+/// would underflow or overflow `usize`. Empty when `diff` is all zero since it would never move.
+pub fn ray<const N: usize>(
+    pos: &[usize; N],
+    diff: &[isize; N],
+) -> impl Iterator<Item = [usize; N]> + use<N> {
+    let diff = *diff;
+    let first = pos.shift(&diff).filter(|_| diff.iter().any(|d| *d != 0));
+    iter::successors(first, move |current| current.shift(&diff))
+}
+
+/// Same as [`ray`] but stops once the position leaves `space`, yielding the value at each step.
+pub fn ray_in_space<'a, T: 'a, S: Space<T, usize, N>, const N: usize>(
+    space: &'a S,
+    pos: &[usize; N],
+    diff: &[isize; N],
+) -> impl Iterator<Item = ([usize; N], &'a T)> + use<'a, T, S, N> {
+    ray(pos, diff).map_while(|current| space.get(&current).map(|val| (current, val)))
 }
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
 use crate::error::Error;
+use crate::hash::FastMap;
 use crate::part_solver;
 use crate::utils::ures;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::BinaryHeap;
 use std::str::FromStr;
 
 part_solver!();
@@ -36,7 +37,7 @@ pub fn part2(input: &str) -> Result<ures, Error> {
         input
             .lines()
             .map(parse_line)
-            .try_fold(HashMap::new(), |mut freqs, line_pair| {
+            .try_fold(FastMap::default(), |mut freqs, line_pair| {
                 line_pair.map(|(left, right)| {
                     freqs
                         .entry(left)
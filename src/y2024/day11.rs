@@ -1,22 +1,25 @@
 use crate::error::Error;
+use crate::math::biguint::BigUint;
+use crate::memo::Memo;
 use crate::part_solver;
-use crate::utils::ures;
 use std::str::FromStr;
 
 part_solver!();
 
-pub fn part1(input: &str) -> Result<ures, Error> {
-    let mut cache = vec![[None; 25]; 100];
-    parse_input(input.trim())
-        .map(|v| v.and_then(|v| blink_count_with_cache(v, 25, &mut cache)))
-        .try_fold(0, |sum, res| res.map(|v| v + sum))
+pub fn part1(input: &str) -> Result<BigUint, Error> {
+    blink_count_sum(input, 25)
+}
+
+pub fn part2(input: &str) -> Result<BigUint, Error> {
+    blink_count_sum(input, 75)
 }
 
-pub fn part2(input: &str) -> Result<ures, Error> {
-    let mut cache = vec![[None; 75]; 1000];
+fn blink_count_sum(input: &str, target_count: u8) -> Result<BigUint, Error> {
+    let mut memo = Memo::default();
+    let mut big_memo = Memo::default();
     parse_input(input.trim())
-        .map(|v| v.and_then(|v| blink_count_with_cache(v, 75, &mut cache)))
-        .try_fold(0, |sum, res| res.map(|v| v + sum))
+        .map(|v| v.and_then(|v| blink_count(v, target_count, &mut memo, &mut big_memo)))
+        .sum()
 }
 
 fn parse_input(input: &str) -> impl Iterator<Item = Result<u64, Error>> + use<'_> {
@@ -27,7 +30,8 @@ fn parse_input(input: &str) -> impl Iterator<Item = Result<u64, Error>> + use<'_
     })
 }
 
-fn blink(val: u64) -> Result<Vec<u64>, Error> {
+/// Stones after one blink, `None` when the stone outgrows u64.
+fn blink(val: u64) -> Option<Vec<u64>> {
     let res = if val == 0 {
         vec![1]
     } else {
@@ -36,41 +40,70 @@ fn blink(val: u64) -> Result<Vec<u64>, Error> {
             let mult = 10u64.pow((count_digit_minus_1 + 1) / 2);
             vec![val / mult, val % mult]
         } else {
-            vec![val.checked_mul(2024).ok_or_else(|| {
-                Error::InvalidState("number is too large, not fitting in u64".into())
-            })?]
+            vec![val.checked_mul(2024)?]
         }
     };
 
-    Ok(res)
+    Some(res)
 }
 
-fn blink_count_with_cache<const N: usize>(
+fn blink_big(val: &BigUint) -> Result<Vec<BigUint>, Error> {
+    if val.is_zero() {
+        return Ok(vec![BigUint::one()]);
+    }
+    let digits = val.to_string();
+    if digits.len().is_multiple_of(2) {
+        let (high, low) = digits.split_at(digits.len() / 2);
+        Ok(vec![high.parse()?, low.parse()?])
+    } else {
+        Ok(vec![val * &BigUint::from(2024u32)])
+    }
+}
+
+/// Stones left from `val` after `target_count` blinks, the stones outgrowing u64 go
+/// through [`blink_count_big`] and share `big_memo` across the whole solve.
+fn blink_count(
     val: u64,
     target_count: u8,
-    cache: &mut Vec<[Option<ures>; N]>,
-) -> Result<ures, Error> {
+    memo: &mut Memo<(u64, u8), BigUint>,
+    big_memo: &mut Memo<(BigUint, u8), BigUint>,
+) -> Result<BigUint, Error> {
     if target_count == 0 {
-        Ok(1)
-    } else {
-        if let Some(Some(count)) = cache
-            .get(val as usize)
-            .and_then(|v| v.get(target_count as usize))
-        {
-            return Ok(*count);
-        }
-        let mut sum = 0;
-        for val in blink(val)?.into_iter() {
-            sum += blink_count_with_cache(val, target_count - 1, cache)?;
-        }
-
-        cache.get_mut(val as usize).and_then(|inner_array| {
-            inner_array
-                .get_mut(target_count as usize)
-                .map(|count| count.replace(sum))
-        });
-        Ok(sum)
+        return Ok(BigUint::one());
+    }
+    if let Some(count) = memo.get(&(val, target_count)) {
+        return Ok(count);
     }
+    let count = match blink(val) {
+        Some(vals) => vals
+            .into_iter()
+            .map(|val| blink_count(val, target_count - 1, memo, big_memo))
+            .sum::<Result<_, _>>()?,
+        None => blink_count_big(
+            BigUint::from(val) * BigUint::from(2024u32),
+            target_count - 1,
+            big_memo,
+        )?,
+    };
+    memo.insert((val, target_count), count.clone())?;
+    Ok(count)
+}
+
+/// [`blink_count`] for the stones too large for u64.
+fn blink_count_big(
+    val: BigUint,
+    target_count: u8,
+    memo: &mut Memo<(BigUint, u8), BigUint>,
+) -> Result<BigUint, Error> {
+    memo.recurse((val, target_count), &|blink_count, (val, target_count)| {
+        if *target_count == 0 {
+            return Ok(BigUint::one());
+        }
+        blink_big(val)?
+            .into_iter()
+            .map(|val| blink_count((val, target_count - 1)))
+            .sum()
+    })
 }
 
 #[cfg(test)]
@@ -78,6 +111,31 @@ mod tests {
     use crate::error::Error;
     use crate::utils::tests_utils::{get_input, human_text_duration};
     use chrono::Utc;
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn count_above_u64() -> Result<(), Error> {
+        assert_eq!(super::blink_count_sum("125 17", 6)?.to_u64(), Some(22));
+        assert_eq!(super::part1("125 17")?.to_u64(), Some(55312));
+
+        let mut stones = HashMap::from([(125u64, 1u128), (17, 1)]);
+        for _ in 0..120 {
+            let mut next = HashMap::new();
+            for (val, count) in stones {
+                for val in super::blink(val).unwrap() {
+                    *next.entry(val).or_default() += count;
+                }
+            }
+            stones = next;
+        }
+        let expected = stones.values().sum::<u128>();
+        assert!(expected > u64::MAX as u128);
+        assert_eq!(
+            super::blink_count_sum("125 17", 120)?.to_u128(),
+            Some(expected)
+        );
+        Ok(())
+    }
 
     #[test]
     pub fn part1() -> Result<(), Error> {
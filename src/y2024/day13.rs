@@ -1,10 +1,11 @@
 use crate::error::{Error, NomError};
+use crate::math::matrix::{Matrix, SolveError};
+use crate::math::MathInt;
 use crate::nom::{
     fold_separated_many0, non_zero_ures, single_line, single_line_not_eof, trim_space, FinalParse,
 };
 use crate::part_solver;
-use crate::utils::ures;
-use gcd::Gcd;
+use crate::utils::{ires, ures};
 use nom::character::complete::space0;
 use nom::sequence::{pair, tuple};
 use nom::{IResult, Parser};
@@ -104,56 +105,22 @@ fn solve_day13_int_matrix(
     c1: ures,
     c2: ures,
 ) -> Result<Option<(ures, ures)>, Error> {
-    let gcd = a1.gcd(a2);
-    let mult_1 = a2 / gcd;
-    let mult_2 = a1 / gcd;
-    let multiplied_b1 = b1
-        .checked_mul(mult_1)
-        .ok_or_else(|| Error::Unsolvable("overflow while matching coefficient".into()))?;
-    let multiplied_b2 = b2
-        .checked_mul(mult_2)
-        .ok_or_else(|| Error::Unsolvable("overflow while matching coefficient".into()))?;
-    let multiplied_c1 = c1
-        .checked_mul(mult_1)
-        .ok_or_else(|| Error::Unsolvable("overflow while matching coefficient".into()))?;
-    let multiplied_c2 = c2
-        .checked_mul(mult_2)
-        .ok_or_else(|| Error::Unsolvable("overflow while matching coefficient".into()))?;
-    if multiplied_b1 == multiplied_b2 {
-        if multiplied_c1 == multiplied_c2 {
-            return Err(Error::Unsolvable("infinite solution".into()));
-        } else {
-            return Ok(None);
-        }
-    }
-
-    let b;
-
-    if multiplied_c1 == multiplied_c2 {
-        b = 0;
-    } else {
-        let ord = multiplied_b2.cmp(&multiplied_b1);
-        if ord != multiplied_c2.cmp(&multiplied_c1) {
-            return Ok(None);
-        }
-
-        let c_diff = multiplied_c1.abs_diff(multiplied_c2);
-        let b_diff = multiplied_b1.abs_diff(multiplied_b2);
-        if c_diff % b_diff != 0 {
-            return Ok(None);
-        } else {
-            b = c_diff / b_diff
+    let matrix = Matrix::from_int_rows([[a1, b1], [a2, b2]])?;
+    let prize = [
+        ires::from_i128(c1.to_i128())?,
+        ires::from_i128(c2.to_i128())?,
+    ];
+    match matrix.solve_integer(&prize) {
+        Ok(presses) => match presses[..] {
+            [a, b] if a >= 0 && b >= 0 => Ok(Some((a as ures, b as ures))),
+            _ => Ok(None),
+        },
+        Err(SolveError::Inconsistent | SolveError::NotInteger(_)) => Ok(None),
+        Err(SolveError::Underdetermined { .. }) => {
+            Err(Error::Unsolvable("infinite solution".into()))
         }
+        Err(e) => Err(e.into()),
     }
-
-    let res = b1
-        .checked_mul(b)
-        .and_then(|b1_final| c1.checked_sub(b1_final))
-        .filter(|c1_final| *c1_final % a1 == 0)
-        .map(|c1_final| c1_final / a1)
-        .map(|a| (a, b));
-
-    Ok(res)
 }
 
 #[cfg(test)]
@@ -1,8 +1,9 @@
 use crate::error::{Error, NomError};
+use crate::hash::FastHashState;
 use crate::nom::{fold_res_many1, single_line_not_eof, FinalParse};
 use crate::part_solver;
 use crate::space::space2d::{Board2d, RefBoard2d};
-use crate::space::{IterSpace, Pos, Space};
+use crate::space::{IterSpace, Pos, Space, SpaceMut};
 use crate::utils::ures;
 use indexmap::IndexSet;
 use nom::character::complete::space0;
@@ -213,7 +214,7 @@ fn parse_board_and_solve_2(input: &str) -> IResult<&str, ures, NomError> {
     let (remaining, _) = single_line_not_eof(space0)
         .context("parse empty line")
         .parse(remaining)?;
-    let mut visited = IndexSet::new();
+    let mut visited = IndexSet::default();
     for (index, &input) in remaining.as_bytes().iter().enumerate() {
         match input {
             b'<' => {
@@ -273,7 +274,7 @@ fn check_vertical_2(
     board: &Board2d<u8>,
     mut queue: VecDeque<[usize; 2]>,
     y_diff: NonZero<isize>,
-    visited: &mut IndexSet<[usize; 2]>,
+    visited: &mut IndexSet<[usize; 2], FastHashState>,
 ) -> Result<bool, Error> {
     while let Some(pos) = queue.pop_front() {
         let [x, y] = pos;
@@ -326,7 +327,7 @@ fn step_vertical_2(
     board: &mut Board2d<u8>,
     pos: &mut [usize; 2],
     y_diff: NonZero<isize>,
-    visited: &mut IndexSet<[usize; 2]>,
+    visited: &mut IndexSet<[usize; 2], FastHashState>,
 ) -> Result<(), Error> {
     let mut queue = VecDeque::new();
     queue.push_back(*pos);
@@ -1,10 +1,14 @@
 use crate::error::{Error, NomError};
-use crate::graph::MaybeProcessed::{Processed, Skipped};
-use crate::graph::{Dijkstra, NeighborFn};
+use crate::graph::predecessor::{PredecessorMode, Predecessors};
+use crate::graph::MaybeProcessed::Processed;
+use crate::graph::{Dial, NeighborFn};
+use crate::hash::FastSet;
 use crate::nom::{fold_res_many1, single_line, FinalParse};
 use crate::part_solver;
+use crate::set::BitIndexSet;
+use crate::space::index::{DirectionIndex, GridIndex, PairIndex};
 use crate::space::space2d::{Direction, RefBoard2d};
-use crate::space::{Pos, Space};
+use crate::space::{Dimension, Pos, Space};
 use crate::utils::ures;
 use derive_more::{Deref, DerefMut, From, Into};
 use nom::error::FromExternalError;
@@ -12,8 +16,7 @@ use nom::error::ParseError;
 use nom::{IResult, Parser, Slice};
 use nom_supreme::ParserExt;
 use std::borrow::Cow;
-use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::VecDeque;
 
 part_solver!();
 type LineAndStartPosAndEndPosCow<'a> = (
@@ -88,6 +91,15 @@ impl NeighborFn<(State, Weight, Metadata2)> for BoardNeighbor<'_> {
     }
 }
 
+fn visited_set(
+    board: &RefBoard2d<u8>,
+) -> BitIndexSet<State, PairIndex<GridIndex<2>, DirectionIndex>> {
+    BitIndexSet::from(PairIndex::from((
+        GridIndex::from(board.dimension()),
+        DirectionIndex,
+    )))
+}
+
 fn calculate_cost(old_direction: Direction, new_direction: Direction) -> ures {
     match new_direction {
         d if d == old_direction => 1,
@@ -99,19 +111,16 @@ fn calculate_cost(old_direction: Direction, new_direction: Direction) -> ures {
 pub fn part1(input: &str) -> Result<ures, Error> {
     let (board, start, end) = parse_input.final_parse(input)?;
 
-    let mut dijkstra = Dijkstra {
-        queue: BinaryHeap::new(),
+    let mut dial = Dial {
+        buckets: VecDeque::new(),
+        base: 0,
+        visited: visited_set(&board),
         neighbor_fn: BoardNeighbor::from(board),
-        visited: HashSet::new(),
     };
-    dijkstra
-        .queue
-        .push(Reverse(((start, Direction::East), 0, ()).into()));
-    dijkstra
-        .queue
-        .push(Reverse(((start, Direction::West), 2000, ()).into()));
+    dial.push_queue(((start, Direction::East), 0, ()))?;
+    dial.push_queue(((start, Direction::West), 2000, ()))?;
     loop {
-        match dijkstra.next() {
+        match dial.next() {
             None => return Err(Error::Unsolvable("cannot find path to end".into())),
             Some(Err(e)) => return Err(e),
             Some(Ok(Processed((state, weight, _metadata)))) => {
@@ -128,100 +137,40 @@ pub fn part1(input: &str) -> Result<ures, Error> {
 pub fn part2(input: &str) -> Result<ures, Error> {
     let (board, start, end) = parse_input.final_parse(input)?;
 
-    let mut dijkstra = Dijkstra {
-        queue: BinaryHeap::new(),
+    let mut dial = Dial {
+        buckets: VecDeque::new(),
+        base: 0,
+        visited: visited_set(&board),
         neighbor_fn: BoardNeighbor::from(board),
-        visited: HashSet::new(),
     };
 
-    let mut best_parents: HashMap<State, (Weight, Vec<State>)> = HashMap::new();
+    let mut predecessors = Predecessors::new(PredecessorMode::All)?;
 
-    dijkstra
-        .queue
-        .push(Reverse(((start, Direction::East), 0, None).into()));
-    dijkstra
-        .queue
-        .push(Reverse(((start, Direction::West), 2000, None).into()));
+    dial.push_queue(((start, Direction::East), 0, None))?;
+    dial.push_queue(((start, Direction::West), 2000, None))?;
 
     let mut end_shortest_len = None;
-    loop {
-        match dijkstra.next() {
-            None => return Err(Error::Unsolvable("cannot find path to end".into())),
-            Some(Err(e)) => return Err(e),
-            Some(Ok(Processed((state, weight, parent)))) => {
-                if let Some(end_shortest_len) = end_shortest_len {
-                    if end_shortest_len < weight {
-                        break;
-                    }
-                }
-                if best_parents
-                    .insert(state, (weight, parent.into_iter().collect()))
-                    .is_some()
-                {
-                    return Err(Error::InvalidState("node is being processed twice".into()));
-                }
-                let (next_pos, _) = state;
-                if next_pos == end {
-                    end_shortest_len.replace(weight);
-                }
-            }
-            Some(Ok(Skipped((state, weight, parent)))) => {
-                if let Some(end_shortest_len) = end_shortest_len {
-                    if end_shortest_len < weight {
-                        break;
-                    }
-                }
-                let (len, parents) = best_parents
-                    .get_mut(&state)
-                    .ok_or(Error::InvalidState("unprocessed node is skipped".into()))?;
-                if *len == weight {
-                    if let Some(parent) = parent {
-                        parents.push(parent)
-                    }
-                }
-            }
+    for res in dial {
+        let item = res?;
+        let ((next_pos, _), weight, _) = item.inner();
+        if end_shortest_len.is_some_and(|end_shortest_len| end_shortest_len < *weight) {
+            break;
         }
-    }
-
-    let mut visited = HashSet::new();
-    visited.insert((start, Direction::East));
-    let (mut work, _) = Direction::cardinal()
-        .iter()
-        .map(|d| State::from((end, *d)))
-        .fold((Vec::new(), None), |(mut vec, mut min_cost), state| {
-            if let Some(&(weight, _)) = best_parents.get(&state) {
-                let stored_min_cost = min_cost.get_or_insert(weight);
-                match (*stored_min_cost).cmp(&weight) {
-                    Ordering::Equal => vec.push(state),
-                    Ordering::Greater => {
-                        vec.clear();
-                        vec.push(state);
-                        *stored_min_cost = weight;
-                    }
-                    Ordering::Less => {}
-                }
+        if let Processed(_) = item {
+            if *next_pos == end {
+                end_shortest_len.get_or_insert(*weight);
             }
-            (vec, min_cost)
-        });
-    while let Some(state) = work.pop() {
-        if visited.contains(&state) {
-            continue;
         }
-        if let Some((_, best_parents_of_state)) = best_parents.get(&state) {
-            best_parents_of_state
-                .iter()
-                .filter(|&s| !visited.contains(s))
-                .for_each(|s| work.push(*s))
-        }
-        visited.insert(state);
+        predecessors.record(&item)?;
+    }
+    if end_shortest_len.is_none() {
+        return Err(Error::Unsolvable("cannot find path to end".into()));
     }
 
-    let pos_set: HashSet<[usize; 2]> = visited
-        .iter()
-        .map(|state| {
-            let &(pos, _) = state;
-            pos
-        })
+    let pos_set: FastSet<[usize; 2]> = predecessors
+        .all_shortest_path_states(Direction::cardinal().iter().map(|d| (end, *d)))
+        .into_iter()
+        .map(|(pos, _)| pos)
         .collect();
 
     Ok(pos_set.len() as ures)
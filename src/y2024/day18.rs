@@ -1,12 +1,15 @@
+use crate::disjoint_set::MappedDisjointSet;
 use crate::error::{Error, NomError};
+use crate::graph::dot::SearchTree;
 use crate::graph::MaybeProcessed::Processed;
-use crate::graph::{Bfs, NeighborFn};
+use crate::graph::{AStar, Manhattan, NeighborFn};
 use crate::nom::{single_line, single_line_not_eof, trim_space, ures, FinalParse};
 use crate::part_solver;
-use crate::set::{BoolSpace, Set};
+use crate::set::BoolSpace;
+use crate::space::index::GridIndex;
 use crate::space::space2d::{Board2d, Direction};
-use crate::space::{Pos, Space};
-use crate::utils::{cardinal, musize, ures};
+use crate::space::{Pos, Space, SpaceMut};
+use crate::utils::{cardinal, dump_graph, dump_graph_enabled, musize, ures};
 use derive_more::{Deref, DerefMut, From};
 use nom::character::complete::char;
 use nom::multi::{many1, many_m_n};
@@ -14,8 +17,7 @@ use nom::sequence::separated_pair;
 use nom::{IResult, Parser};
 use nom_supreme::ParserExt;
 use std::borrow::BorrowMut;
-use std::collections::VecDeque;
-use std::ops::ControlFlow::{Break, Continue};
+use std::collections::BinaryHeap;
 
 part_solver!();
 
@@ -25,17 +27,18 @@ struct NonCorruptedNeighbor<'a, Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>>(
 );
 
 type State = [usize; 2];
-type Metadata = ures;
-type Metadata2 = ();
+type Weight = ures;
+/// Parent state, for [`SearchTree`].
+type Metadata = Option<State>;
 
-impl<Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>> NeighborFn<(State, Metadata)>
+impl<Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>> NeighborFn<(State, Weight, Metadata)>
     for NonCorruptedNeighbor<'_, Vy, Vx>
 {
     fn get_neighbors(
         &mut self,
-        sm: &(State, Metadata),
-    ) -> impl IntoIterator<Item = (State, Metadata)> {
-        let (state, cost) = &sm;
+        swm: &(State, Weight, Metadata),
+    ) -> impl IntoIterator<Item = (State, Weight, Metadata)> {
+        let (state, cost, _) = &swm;
         let board = self.deref_mut();
         let pos = state;
         let res = Direction::cardinal()
@@ -44,28 +47,7 @@ impl<Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>> NeighborFn<(State, Metadata)>
                 pos.shift(new_direction.get_movement_vec())
                     .filter(|new_pos| board.get(new_pos) == Some(&false))
             })
-            .map(|new_state| (new_state, *cost + 1));
-        res
-    }
-}
-
-impl<Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>> NeighborFn<(State, Metadata2)>
-    for NonCorruptedNeighbor<'_, Vy, Vx>
-{
-    fn get_neighbors(
-        &mut self,
-        sm: &(State, Metadata2),
-    ) -> impl IntoIterator<Item = (State, Metadata2)> {
-        let (state, _) = &sm;
-        let board = self.deref_mut();
-        let pos = state;
-        let res = Direction::cardinal()
-            .iter()
-            .filter_map(|new_direction| {
-                pos.shift(new_direction.get_movement_vec())
-                    .filter(|new_pos| board.get(new_pos) == Some(&false))
-            })
-            .map(|new_state| (new_state, ()));
+            .map(|new_state| (new_state, *cost + 1, Some(*pos)));
         res
     }
 }
@@ -78,23 +60,33 @@ pub fn part1(input: &str) -> Result<ures, Error> {
         .try_for_each(|(x, y)| board.set(&[x, y], true).map(|_| ()))
         .ok_or_else(|| Error::InvalidState("out of bound".into()))?;
 
-    let mut bfs = Bfs {
-        queue: VecDeque::new(),
+    let mut a_star = AStar {
+        queue: BinaryHeap::new(),
         neighbor_fn: NonCorruptedNeighbor::from(&mut board),
         visited: BoolSpace::from(Board2d::from([[false; 71]; 71])),
+        heuristic: Manhattan::from([70, 70]),
     };
-    bfs.queue.push_back(([0, 0], 0));
+    a_star.push_queue(([0, 0], 0, None));
 
+    let mut tree =
+        dump_graph_enabled().then(|| SearchTree::with_map(Board2d::from([[None; 71]; 71])));
     loop {
-        match bfs.next() {
-            None => return Err(Error::Unsolvable("cannot find path to end".into())),
-            Some(Err(e)) => return Err(e),
-            Some(Ok(Processed((state, cost)))) => {
-                if state == [70, 70] {
-                    return Ok(cost);
-                }
+        let item = a_star
+            .next()
+            .ok_or_else(|| Error::Unsolvable("cannot find path to end".into()))??;
+        if let Some(tree) = tree.as_mut() {
+            tree.record(&item)?;
+        }
+        if let Processed(([70, 70], cost, _)) = item {
+            if let Some(tree) = tree {
+                dump_graph("2024_day18", || {
+                    tree.to_dot_with(
+                        |[x, y]| format!("{},{}", x, y),
+                        |cost| Some(cost.to_string()),
+                    )
+                })?;
             }
-            _ => continue,
+            return Ok(cost);
         }
     }
 }
@@ -107,56 +99,23 @@ pub fn part2(input: &str) -> Result<String, Error> {
         .try_for_each(|(x, y)| board.set(&[*x, *y], true).map(|_| ()))
         .ok_or_else(|| Error::InvalidState("out of bound".into()))?;
 
-    let mut bfs = Bfs {
-        queue: VecDeque::new(),
-        neighbor_fn: NonCorruptedNeighbor::from(&mut board),
-        visited: BoolSpace::from(Board2d::from([[false; 71]; 71])),
-    };
-    bfs.queue.push_back(([0, 0], ()));
-
-    loop {
-        match bfs.next() {
-            None => break,
-            Some(Err(e)) => return Err(e),
-            Some(Ok(Processed((state, _)))) => {
-                if state == [70, 70] {
-                    return Err(Error::Unsolvable(
-                        "reach exit without remove any block!".into(),
-                    ));
-                }
-            }
-            _ => continue,
+    let mut regions = MappedDisjointSet::new(GridIndex::from([71, 71]));
+    for y in 0..71 {
+        for x in 0..71 {
+            join_free_neighbors(&mut regions, &board, [x, y])?;
         }
     }
+    if regions.same(&[0, 0], &[70, 70])? {
+        return Err(Error::Unsolvable(
+            "reach exit without remove any block!".into(),
+        ));
+    }
 
     for (rm_x, rm_y) in corrupted.into_iter().rev() {
-        bfs.neighbor_fn.deref_mut().set(&[rm_x, rm_y], false);
-
-        if let Break(res) = cardinal(&[rm_x, rm_y])
-            .filter(|p| bfs.neighbor_fn.deref().get(p).is_some())
-            .try_fold((), |_, elem| {
-                let res = bfs.visited.contains(&elem);
-                if res == Ok(false) {
-                    Continue(())
-                } else {
-                    Break(res)
-                }
-            })
-        {
-            res?;
-            bfs.queue.push_back(([rm_x, rm_y], ()));
-            loop {
-                match bfs.next() {
-                    None => break,
-                    Some(Err(e)) => return Err(e),
-                    Some(Ok(Processed((state, _)))) => {
-                        if state == [70, 70] {
-                            return Ok(format!("{},{}", rm_x, rm_y));
-                        }
-                    }
-                    _ => continue,
-                }
-            }
+        board.set(&[rm_x, rm_y], false);
+        join_free_neighbors(&mut regions, &board, [rm_x, rm_y])?;
+        if regions.same(&[0, 0], &[70, 70])? {
+            return Ok(format!("{},{}", rm_x, rm_y));
         }
     }
 
@@ -165,6 +124,20 @@ pub fn part2(input: &str) -> Result<String, Error> {
     ))
 }
 
+fn join_free_neighbors<Vy: BorrowMut<[Vx]>, Vx: BorrowMut<[bool]>>(
+    regions: &mut MappedDisjointSet<[usize; 2], GridIndex<2>>,
+    board: &Board2d<bool, Vy, Vx>,
+    pos: [usize; 2],
+) -> Result<(), Error> {
+    if board.get(&pos) != Some(&false) {
+        return Ok(());
+    }
+    for neighbor in cardinal(&pos).filter(|p| board.get(p) == Some(&false)) {
+        regions.union(&pos, &neighbor)?;
+    }
+    Ok(())
+}
+
 fn parse_coord_line(input: &str) -> IResult<&str, (usize, usize), NomError<'_>> {
     separated_pair(
         ures.map(|v| v as musize),
@@ -1,7 +1,8 @@
 use crate::error::{Error, NomError};
+use crate::memo::{DenseStore, Memo};
 use crate::nom::{fold_separated_res_many1, single_line_not_eof, trim_space, FinalParse};
 use crate::part_solver;
-use crate::trie::{ArrayTrie, TrieNode};
+use crate::trie::{CompactTrie, TrieNode};
 use crate::utils::ures;
 use nom::character::complete::{char, line_ending, multispace0, space0};
 use nom::error::ParseError;
@@ -14,15 +15,16 @@ use std::iter::Peekable;
 
 part_solver!();
 
+type Towels = CompactTrie<5>;
+
 pub fn part1(input: &str) -> Result<ures, Error> {
     let (towels, designs) = input_parser.final_parse(input)?;
-    let mut cache = Vec::new();
     designs
         .into_iter()
         .map(|design| design.collect::<Vec<_>>())
         .map(|d| {
-            cache.clear();
-            can_match(d.iter().peekable(), 0, &towels, &towels, &mut cache, true)
+            let mut memo = Memo::from(DenseStore::with_bound(d.len() + 1));
+            can_match(d.iter().peekable(), 0, &towels, &towels, &mut memo, true)
         })
         .try_fold(0, |mut count, res| {
             if res? {
@@ -34,12 +36,11 @@ pub fn part1(input: &str) -> Result<ures, Error> {
 
 pub fn part2(input: &str) -> Result<ures, Error> {
     let (towels, designs) = input_parser.final_parse(input)?;
-    let mut cache = Vec::new();
     designs
         .into_iter()
         .map(|design| {
-            cache.clear();
-            match_count(design.peekable(), 0, &towels, &towels, &mut cache, true)
+            let mut memo = Memo::from(DenseStore::with_bound(design.clone().count() + 1));
+            match_count(design.peekable(), 0, &towels, &towels, &mut memo, true)
         })
         .try_fold(0, |sum, res| res.map(|v| sum + v))
 }
@@ -93,9 +94,9 @@ fn parse_towels<T: TrieNode<usize>>(input: &str) -> IResult<&str, T, NomError> {
 
 fn input_parser(
     input: &str,
-) -> IResult<&str, (ArrayTrie<5>, Vec<impl Iterator<Item = usize> + Clone + '_>), NomError> {
+) -> IResult<&str, (Towels, Vec<impl Iterator<Item = usize> + Clone + '_>), NomError<'_>> {
     separated_pair(
-        single_line_not_eof(parse_towels::<ArrayTrie<5>>),
+        single_line_not_eof(parse_towels::<Towels>),
         single_line_not_eof(space0),
         separated_list1(line_ending, trim_space(parse_word)).terminated(multispace0),
     )
@@ -106,12 +107,12 @@ fn can_match<T, R: Borrow<T> + Clone, I: ExactSizeIterator<Item = R> + Clone, Tr
     pos: usize,
     trie: &Trie,
     root: &Trie,
-    cache: &mut Vec<Option<bool>>,
+    memo: &mut Memo<usize, bool, DenseStore<bool>>,
     first: bool,
 ) -> Result<bool, Error> {
     if first {
-        if let Some(Some(v)) = cache.get(pos) {
-            return Ok(*v);
+        if let Some(v) = memo.get(&pos) {
+            return Ok(v);
         }
     }
     match trie.find_prefix(&mut iter)? {
@@ -121,18 +122,16 @@ fn can_match<T, R: Borrow<T> + Clone, I: ExactSizeIterator<Item = R> + Clone, Tr
                 return Ok(true);
             }
             let n_pos = pos + node.depth();
-            let matched = can_match(iter.clone(), n_pos, root, root, cache, true)?
-                || can_match(iter, pos, node, root, cache, false)?;
+            let matched = can_match(iter.clone(), n_pos, root, root, memo, true)?
+                || can_match(iter, pos, node, root, memo, false)?;
             Ok(matched)
         }
     }
-    .inspect(|matched| {
+    .and_then(|matched| {
         if first {
-            if pos >= cache.len() {
-                cache.resize(pos + 1, None);
-            }
-            cache[pos].replace(*matched);
+            memo.insert(pos, matched)?;
         }
+        Ok(matched)
     })
 }
 
@@ -141,12 +140,12 @@ fn match_count<T, R: Borrow<T> + Clone, I: Iterator<Item = R> + Clone, Trie: Tri
     pos: usize,
     trie: &Trie,
     root: &Trie,
-    cache: &mut Vec<Option<ures>>,
+    memo: &mut Memo<usize, ures, DenseStore<ures>>,
     first: bool,
 ) -> Result<ures, Error> {
     if first {
-        if let Some(Some(v)) = cache.get(pos) {
-            return Ok(*v);
+        if let Some(v) = memo.get(&pos) {
+            return Ok(v);
         }
     }
     match trie.find_prefix(&mut iter)? {
@@ -156,18 +155,16 @@ fn match_count<T, R: Borrow<T> + Clone, I: Iterator<Item = R> + Clone, Trie: Tri
                 return Ok(1);
             }
             let n_pos = pos + node.depth();
-            let count = match_count(iter.clone(), n_pos, root, root, cache, true)?
-                + match_count(iter, pos, node, root, cache, false)?;
+            let count = match_count(iter.clone(), n_pos, root, root, memo, true)?
+                + match_count(iter, pos, node, root, memo, false)?;
             Ok(count)
         }
     }
-    .inspect(|count| {
+    .and_then(|count| {
         if first {
-            if pos >= cache.len() {
-                cache.resize(pos + 1, None);
-            }
-            cache[pos].replace(*count);
+            memo.insert(pos, count)?;
         }
+        Ok(count)
     })
 }
 
@@ -1,21 +1,17 @@
 use crate::error::{Error, NomError};
-use crate::graph::MaybeProcessed::Processed;
-use crate::graph::{Bfs, NeighborFn};
+use crate::graph::distance::distance_field;
 use crate::nom::{fold_res_many1, single_line, FinalParse};
 use crate::part_solver;
-use crate::set::OptionSpace;
-use crate::space::space2d::{Board2d, Direction, RefBoard2d};
+use crate::space::space2d::{Board2d, RefBoard2d};
+use crate::space::IterSpace;
 use crate::space::Space;
-use crate::space::{IterSpace, Pos};
-use crate::utils::ures;
-use derive_more::{Deref, From};
+use crate::utils::{cardinal, ures};
+use derive_more::From;
 use nom::error::FromExternalError;
 use nom::error::ParseError;
 use nom::{IResult, Parser, Slice};
 use nom_supreme::ParserExt;
 use std::borrow::Cow;
-use std::collections::VecDeque;
-use std::ops::ControlFlow::{Break, Continue};
 
 part_solver!();
 
@@ -27,70 +23,6 @@ type LineAndStartPosAndEndPosCow<'a> = (
 
 type BoardAndStartPosAndEndPos<'a> = (RefBoard2d<'a, u8>, [usize; 2], [usize; 2]);
 
-struct WalkableNeighbor<'a> {
-    board: &'a RefBoard2d<'a, u8>,
-    start: [usize; 2],
-    end: [usize; 2],
-    error: Option<Error>,
-}
-
-type State = [usize; 2];
-type Metadata = ();
-
-impl NeighborFn<(State, Metadata)> for WalkableNeighbor<'_> {
-    fn get_neighbors(
-        &mut self,
-        sm: &(State, Metadata),
-    ) -> impl IntoIterator<Item = (State, Metadata)> {
-        if self.error.is_some() {
-            return Vec::default();
-        }
-        let (state, _) = &sm;
-        let board = self.board;
-        let pos = state;
-        let res = Direction::cardinal()
-            .iter()
-            .filter_map(|new_direction| {
-                pos.shift(new_direction.get_movement_vec())
-                    .filter(|new_pos| board.get(new_pos) != Some(&b'#'))
-            })
-            .map(|new_state| (new_state, ()))
-            .try_fold(Vec::with_capacity(2), |mut v, s| {
-                if v.len() == 2 {
-                    self.error.replace(Error::InvalidState(
-                        format!(
-                            "there are path intersection which is not supported: {:?}",
-                            pos
-                        )
-                        .into(),
-                    ));
-                    Break(Vec::default())
-                } else {
-                    v.push(s);
-                    Continue(v)
-                }
-            });
-
-        let v = match res {
-            Continue(v) => v,
-            Break(v) => v,
-        };
-
-        if self.error.is_none() {
-            if pos == &self.start || pos == &self.end {
-                if v.len() != 1 {
-                    self.error.replace(Error::InvalidState(
-                        "starting or ending position is not terminated".into(),
-                    ));
-                }
-            } else if v.len() != 2 {
-                unreachable!("every middle position should have 2 neighbor")
-            }
-        }
-        v
-    }
-}
-
 pub fn part1(input: &str) -> Result<ures, Error> {
     inner_solver::<2>(input)
 }
@@ -102,45 +34,50 @@ pub fn part2(input: &str) -> Result<ures, Error> {
 fn inner_solver<const N: usize>(input: &str) -> Result<ures, Error> {
     let (board, start, end) = parse_input.final_parse(input)?;
 
-    let distance_from_start = Board2d::from(
-        board
-            .deref()
-            .iter()
-            .map(|line| vec![None; line.len()])
-            .collect::<Vec<_>>(),
-    );
-    let mut bfs = Bfs {
-        queue: VecDeque::new(),
-        neighbor_fn: WalkableNeighbor {
-            board: &board,
-            start,
-            end,
-            error: None,
-        },
-        visited: OptionSpace::from(distance_from_start),
-    };
-    bfs.queue.push_back((start, ()));
+    let distance_from_start = distance_field(&board, start, |val| *val != b'#')?;
+    if distance_from_start.get(&end) == Some(&None) {
+        return Err(Error::Unsolvable("cannot find path to end".into()));
+    }
+    check_single_track(&board, &distance_from_start, start, end)?;
 
-    let mut distance = 0;
-    loop {
-        match bfs.next() {
-            None => return Err(Error::Unsolvable("cannot find path to end".into())),
-            Some(Err(e)) => return Err(e),
-            Some(Ok(Processed((pos, _)))) => {
-                if let Some(error) = bfs.neighbor_fn.error.take() {
-                    return Err(error);
-                }
-                bfs.visited.set(&pos, Some(distance));
-                distance += 1;
-                if pos == end {
-                    break;
-                }
+    Ok(count_cheat(&distance_from_start, N, |saved| saved >= 100))
+}
+
+/// [`count_cheat`] relies on the track being a single path without branches from start to end.
+fn check_single_track(
+    board: &RefBoard2d<u8>,
+    distance_from_start: &Board2d<Option<usize>>,
+    start: [usize; 2],
+    end: [usize; 2],
+) -> Result<(), Error> {
+    for (pos, distance) in distance_from_start.iter() {
+        if distance.is_none() {
+            continue;
+        }
+        let neighbor_count = cardinal(&pos)
+            .filter(|next| board.get(next).is_some_and(|val| *val != b'#'))
+            .count();
+        if pos == start || pos == end {
+            if neighbor_count != 1 {
+                return Err(Error::InvalidState(
+                    "starting or ending position is not terminated".into(),
+                ));
             }
-            _ => continue,
+        } else if neighbor_count > 2 {
+            return Err(Error::InvalidState(
+                format!(
+                    "there are path intersection which is not supported: {:?}",
+                    pos
+                )
+                .into(),
+            ));
+        } else if neighbor_count < 2 {
+            return Err(Error::InvalidState(
+                format!("there are dead end which is not supported: {:?}", pos).into(),
+            ));
         }
     }
-
-    Ok(count_cheat(bfs.visited.deref(), N, |saved| saved >= 100))
+    Ok(())
 }
 
 fn parse_board_line<'a>(
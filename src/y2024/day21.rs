@@ -1,6 +1,7 @@
 use crate::error::{Error, NomError};
 use crate::graph::MaybeProcessed::Processed;
 use crate::graph::{Dijkstra, NeighborFn};
+use crate::hash::FastSet;
 use crate::nom::{single_line, FinalParse};
 use crate::part_solver;
 use crate::utils::ures;
@@ -8,7 +9,7 @@ use nom::error::FromExternalError;
 use nom::multi::many1;
 use nom::{IResult, Parser};
 use std::cmp::PartialEq;
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::BinaryHeap;
 use std::slice::Iter;
 
 part_solver!();
@@ -45,7 +46,7 @@ fn calculate_min_cost<I: Iterator<Item = NumPad>, II: IntoIterator<IntoIter = I,
             target: NumPad::A,
             cost_table,
         },
-        visited: HashSet::new(),
+        visited: FastSet::default(),
     };
 
     let mut current = ((NumPad::A, KeyPad::A), 0, ());
@@ -1,66 +1,38 @@
 use crate::error::{Error, NomError};
+use crate::graph::adjacency::Graph;
 use crate::nom::{single_line, FinalParse};
 use crate::part_solver;
-use crate::utils::ures;
-use indexmap::{IndexMap, IndexSet};
+use crate::utils::{dump_graph, ures};
 use nom::character::complete::{alpha1, char};
 use nom::multi::many1;
 use nom::sequence::separated_pair;
 use nom::{IResult, Parser};
 use nom_supreme::ParserExt;
-use std::cell::RefCell;
-use std::collections::{BTreeSet, HashSet};
 
 part_solver!();
 
 pub fn part1(input: &str) -> Result<ures, Error> {
     let edges = parse_input.final_parse(input)?;
-    let mut neighbor = IndexMap::new();
+    let mut graph = Graph::undirected();
     for (v1, v2) in edges {
-        let entry_1 = neighbor.entry(v1);
-        let idx_1 = entry_1.index();
-        entry_1.or_insert_with(|| {
-            if v1.starts_with('t') {
-                (true, RefCell::new(HashSet::new()))
-            } else {
-                (false, RefCell::new(HashSet::new()))
-            }
-        });
-        let entry_2 = neighbor.entry(v2);
-        let idx_2 = entry_2.index();
-        entry_2.or_insert_with(|| {
-            if v2.starts_with('t') {
-                (true, RefCell::new(HashSet::new()))
-            } else {
-                (false, RefCell::new(HashSet::new()))
-            }
-        });
-        let (_, (_, neighbor1)) = neighbor.get_index_mut(idx_1).unwrap();
-        neighbor1.get_mut().insert(idx_2);
-        let (_, (_, neighbor2)) = neighbor.get_index_mut(idx_2).unwrap();
-        neighbor2.get_mut().insert(idx_1);
+        graph.add_edge_unique(v1, v2, ());
     }
 
-    let res = neighbor
-        .values()
-        .enumerate()
-        .filter(|(_node, (start_t, _neighbor))| *start_t)
-        .map(|(node, (_start_t, my_neighbor))| {
-            let mut sum = 0;
-            let binding = my_neighbor.borrow();
-            let mut iter = binding.iter();
-            while let Some(first_neighbor) = iter.next() {
-                let iter = iter.clone();
-                let (_, (_, first_neighbor_neighbors)) =
-                    neighbor.get_index(*first_neighbor).unwrap();
-                first_neighbor_neighbors.borrow_mut().remove(&node);
-                for second_neighbor in iter {
-                    if first_neighbor_neighbors.borrow().contains(second_neighbor) {
-                        sum += 1
-                    }
-                }
-            }
-            sum
+    let start_t = |id: usize| graph.node(id).unwrap().starts_with('t');
+    let res = graph
+        .node_ids()
+        .map(|v1| {
+            graph
+                .neighbors(v1)
+                .filter(|v2| *v2 > v1)
+                .map(|v2| {
+                    graph
+                        .neighbors(v2)
+                        .filter(|v3| *v3 > v2 && graph.has_edge(v1, *v3))
+                        .filter(|v3| start_t(v1) || start_t(v2) || start_t(*v3))
+                        .count() as ures
+                })
+                .sum::<ures>()
         })
         .sum();
     Ok(res)
@@ -68,40 +40,20 @@ pub fn part1(input: &str) -> Result<ures, Error> {
 
 pub fn part2(input: &str) -> Result<String, Error> {
     let edges = parse_input.final_parse(input)?;
-    let mut neighbor = IndexMap::new();
+    let mut graph = Graph::undirected();
     for (v1, v2) in edges {
-        let entry_1 = neighbor.entry(v1);
-        let idx_1 = entry_1.index();
-        entry_1.or_insert_with(IndexSet::new);
-        let entry_2 = neighbor.entry(v2);
-        let idx_2 = entry_2.index();
-        entry_2.or_insert_with(IndexSet::new);
-        let (_, neighbor1) = neighbor.get_index_mut(idx_1).unwrap();
-        neighbor1.insert(idx_2);
-        let (_, neighbor2) = neighbor.get_index_mut(idx_2).unwrap();
-        neighbor2.insert(idx_1);
+        graph.add_edge_unique(v1, v2, ());
     }
+    dump_graph("2024_day23", || graph.to_dot())?;
 
-    let potentials = (0..neighbor.len()).collect::<IndexSet<_>>();
-    let mut all_cliques = HashSet::new();
-    bron_kerbosch(
-        &BTreeSet::new(),
-        potentials,
-        IndexSet::new(),
-        &neighbor,
-        &mut all_cliques,
-    );
-    let mut largest_clique = all_cliques
+    let mut largest_clique = graph
+        .maximum_clique()
         .into_iter()
-        .max_by_key(|clique| clique.len())
-        .ok_or_else(|| Error::InvalidState("no clique found".into()))?
-        .into_iter()
-        .map(|index| {
-            let (name, _) = neighbor.get_index(index).unwrap();
-            name
-        })
-        .cloned()
+        .map(|id| *graph.node(id).unwrap())
         .collect::<Vec<_>>();
+    if largest_clique.is_empty() {
+        return Err(Error::InvalidState("no clique found".into()));
+    }
 
     largest_clique.sort();
     Ok(largest_clique.join(","))
@@ -117,35 +69,6 @@ fn parse_input(input: &str) -> IResult<&str, Vec<(&str, &str)>, NomError> {
     many1(single_line(parse_edge)).parse(input)
 }
 
-fn bron_kerbosch(
-    current_clique: &BTreeSet<usize>,
-    mut potentials: IndexSet<usize>,
-    mut visited: IndexSet<usize>,
-    graph: &IndexMap<&str, IndexSet<usize>>,
-    cliques: &mut HashSet<BTreeSet<usize>>,
-) {
-    while let Some(&v) = potentials.last() {
-        let mut next_clique = current_clique.clone();
-        next_clique.insert(v);
-        let (_, neighbor) = graph.get_index(v).unwrap();
-        let next_potentials = potentials
-            .intersection(neighbor)
-            .cloned()
-            .collect::<IndexSet<usize>>();
-        let next_visited = visited
-            .intersection(neighbor)
-            .cloned()
-            .collect::<IndexSet<usize>>();
-        if next_potentials.is_empty() && next_visited.is_empty() {
-            cliques.insert(next_clique);
-        } else {
-            bron_kerbosch(&next_clique, next_potentials, next_visited, graph, cliques);
-        }
-        potentials.swap_remove(&v);
-        visited.insert(v);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
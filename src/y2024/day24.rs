@@ -1,7 +1,9 @@
 use crate::error::{Error, NomError};
+use crate::graph::adjacency::Graph;
+use crate::hash::{FastMap, FastSet};
 use crate::nom::{fold_res_many1, single_line, single_line_not_eof, FinalParse};
 use crate::part_solver;
-use crate::utils::ures;
+use crate::utils::{dump_graph, ures};
 use nom::branch::alt;
 use nom::bytes::complete::take_while_m_n;
 use nom::character::complete::{char, space0, u8};
@@ -12,13 +14,12 @@ use nom::IResult;
 use nom::Parser;
 use nom_supreme::tag::complete::tag;
 use nom_supreme::ParserExt;
-use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
 
 part_solver!();
 
-type WireToChildGateMap<'a> = HashMap<Wire<'a>, Vec<Rc<Gate<'a>>>>;
+type WireToChildGateMap<'a> = FastMap<Wire<'a>, Vec<Rc<Gate<'a>>>>;
 
 pub fn part1(_input: &str) -> Result<ures, Error> {
     let (mut processed, children) = parse_input.final_parse(_input)?;
@@ -69,7 +70,15 @@ pub fn part2(input: &str) -> Result<String, Error> {
         })
         .max()
         .ok_or_else(|| Error::ParseError("no z output found".into()))?;
-    let mut wrong_output = HashSet::new();
+    dump_graph("2024_day24", || {
+        let mut graph = Graph::directed();
+        for gate in gates.iter() {
+            graph.add_edge(gate.input_1, gate.output, gate.op);
+            graph.add_edge(gate.input_2, gate.output, gate.op);
+        }
+        graph.to_dot_with(|wire| wire.to_string(), |op| Some(format!("{:?}", op)))
+    })?;
+    let mut wrong_output = FastSet::default();
     for gate in gates {
         let (&input_1, &input_2, &op, &output) =
             (&gate.input_1, &gate.input_2, &gate.op, &gate.output);
@@ -270,11 +279,11 @@ fn parse_gate(input: &str) -> IResult<&str, Gate, NomError> {
     .parse(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, (HashMap<Wire, bool>, WireToChildGateMap), NomError> {
+fn parse_input(input: &str) -> IResult<&str, (FastMap<Wire, bool>, WireToChildGateMap), NomError> {
     separated_pair(
         fold_res_many1(
             single_line_not_eof(parse_node),
-            HashMap::new,
+            FastMap::default,
             |mut inputs, (wire, val)| {
                 if Some(!val) == inputs.insert(wire, val) {
                     Err((
@@ -292,8 +301,8 @@ fn parse_input(input: &str) -> IResult<&str, (HashMap<Wire, bool>, WireToChildGa
         single_line(space0),
         fold_many1(
             single_line(parse_gate),
-            HashMap::new,
-            |mut children: HashMap<Wire<'_>, Vec<Rc<_>>>, gate: Gate| {
+            FastMap::default,
+            |mut children: FastMap<Wire<'_>, Vec<Rc<_>>>, gate: Gate| {
                 let wire_1 = gate.input_1;
                 let wire_2 = gate.input_2;
                 let gate = Rc::new(gate);
@@ -311,7 +320,7 @@ fn parse_input_2(input: &str) -> IResult<&str, (Vec<Rc<Gate>>, WireToChildGateMa
         .precedes(single_line(space0))
         .precedes(fold_many1(
             single_line(parse_gate),
-            || (Vec::new(), HashMap::new()),
+            || (Vec::new(), FastMap::default()),
             |(mut ops, mut children), gate: Gate| {
                 let wire_1 = gate.input_1;
                 let wire_2 = gate.input_2;
@@ -1,160 +1,103 @@
 use crate::error::Error;
 use crate::part_solver;
-use crate::utils::ures;
+use crate::space::space2d::{Direction8, RefBoard2d};
+use crate::space::view::{Rotated, Rotation};
+use crate::space::{IterSpace, Pos, Space};
+use crate::utils::{ray_in_space, ures};
+use std::borrow::Cow;
 
 part_solver!();
 
-pub fn part1(input: &str) -> Result<ures, Error> {
-    let (board, width) = parse_input(input)?;
-    let mut sum = 0;
-    if width == 0 {
-        return Ok(0);
-    }
+const ROTATIONS: [Rotation; 3] = [
+    Rotation::Clockwise90,
+    Rotation::Clockwise180,
+    Rotation::Clockwise270,
+];
 
-    for y in 0..board.len() {
-        for x in 0..width {
-            if match_horizontal(x, y, width, &board, "XMAS") {
-                sum += 1;
-            }
-            if match_vertical(x, y, &board, "XMAS") {
-                sum += 1;
-            }
-            if match_diagonal_down_right(x, y, width, &board, "XMAS") {
-                sum += 1;
-            }
-            if match_diagonal_up_right_from_bottom_left(x, y, width, &board, "XMAS") {
-                sum += 1;
-            }
-            if match_horizontal(x, y, width, &board, "SAMX") {
-                sum += 1;
-            }
-            if match_vertical(x, y, &board, "SAMX") {
-                sum += 1;
-            }
-            if match_diagonal_down_right(x, y, width, &board, "SAMX") {
-                sum += 1;
-            }
-            if match_diagonal_up_right_from_bottom_left(x, y, width, &board, "SAMX") {
-                sum += 1;
-            }
-        }
-    }
-
-    Ok(sum)
+pub fn part1(input: &str) -> Result<ures, Error> {
+    let board = parse_input(input)?;
+    let res = count_xmas(&board)
+        + ROTATIONS
+            .iter()
+            .map(|rotation| count_xmas(&Rotated::new(&board, *rotation)))
+            .sum::<ures>();
+
+    Ok(res)
 }
 
 pub fn part2(input: &str) -> Result<ures, Error> {
-    let (board, width) = parse_input(input)?;
-    let mut sum = 0;
-    if width == 0 {
-        return Ok(0);
-    }
-
-    for y in 0..board.len() {
-        for x in 0..width {
-            let down_right = match_diagonal_down_right(x, y, width, &board, "MAS")
-                || match_diagonal_down_right(x, y, width, &board, "SAM");
-            if down_right {
-                let up_right_from_bottom_left =
-                    match_diagonal_up_right_from_bottom_left(x, y, width, &board, "MAS")
-                        || match_diagonal_up_right_from_bottom_left(x, y, width, &board, "SAM");
-
-                if up_right_from_bottom_left {
-                    sum += 1;
-                }
-            }
-        }
-    }
-
-    Ok(sum)
+    let board = parse_input(input)?;
+    let res = count_cross(&board)
+        + ROTATIONS
+            .iter()
+            .map(|rotation| count_cross(&Rotated::new(&board, *rotation)))
+            .sum::<ures>();
+
+    Ok(res)
 }
 
-// This is full retarded solution..., have function for every type of match :X
-
-fn match_horizontal(x: usize, y: usize, width: usize, board: &[&str], s: &str) -> bool {
-    let len = s.len();
-    if x + len <= width {
-        let mut i = 0;
-        while i < len {
-            if board[y].as_bytes()[x + i] != s.as_bytes()[i] {
-                return false;
-            }
-            i += 1;
-        }
-        return true;
-    }
-    false
+/// `XMAS` read eastward or south-eastward, the rotations cover the other six directions.
+fn count_xmas<S: Space<u8, usize, 2> + IterSpace<u8, usize, 2>>(space: &S) -> ures {
+    space
+        .iter()
+        .filter(|(_pos, &val)| val == b'X')
+        .map(|(pos, _val)| {
+            [Direction8::East, Direction8::SouthEast]
+                .iter()
+                .filter(|direction| match_ray(space, &pos, direction, b"MAS"))
+                .count() as ures
+        })
+        .sum()
 }
 
-fn match_vertical(x: usize, y: usize, board: &[&str], s: &str) -> bool {
-    let len = s.len();
-    if y + len <= board.len() {
-        let mut i = 0;
-        while i < len {
-            if board[y + i].as_bytes()[x] != s.as_bytes()[i] {
-                return false;
-            }
-            i += 1;
-        }
-        return true;
-    }
-    false
+/// Crossed `MAS` with both `M` on the north side, each cross only matches in one rotation.
+fn count_cross<S: Space<u8, usize, 2> + IterSpace<u8, usize, 2>>(space: &S) -> ures {
+    space
+        .iter()
+        .filter(|(_pos, &val)| val == b'A')
+        .filter(|(pos, _val)| {
+            let get = |direction: Direction8| {
+                pos.shift(direction.get_movement_vec())
+                    .and_then(|p| space.get(&p))
+                    .copied()
+            };
+            get(Direction8::NorthWest) == Some(b'M')
+                && get(Direction8::NorthEast) == Some(b'M')
+                && get(Direction8::SouthWest) == Some(b'S')
+                && get(Direction8::SouthEast) == Some(b'S')
+        })
+        .count() as ures
 }
 
-fn match_diagonal_down_right(x: usize, y: usize, width: usize, board: &[&str], s: &str) -> bool {
-    let len = s.len();
-    if x + len <= width && y + len <= board.len() {
-        let mut i = 0;
-        while i < len {
-            if board[y + i].as_bytes()[x + i] != s.as_bytes()[i] {
-                return false;
-            }
-            i += 1;
-        }
-        return true;
-    }
-    false
-}
-
-fn match_diagonal_up_right_from_bottom_left(
-    x: usize,
-    y: usize,
-    width: usize,
-    board: &[&str],
-    s: &str,
+fn match_ray<S: Space<u8, usize, 2>>(
+    space: &S,
+    pos: &[usize; 2],
+    direction: &Direction8,
+    s: &[u8],
 ) -> bool {
-    let len = s.len();
-    if x + len <= width && y + len <= board.len() {
-        let mut i = 0;
-        while i < len {
-            if board[y + len - i - 1].as_bytes()[x + i] != s.as_bytes()[i] {
-                return false;
-            }
-            i += 1;
-        }
-        return true;
-    }
-    false
+    let mut ray = ray_in_space(space, pos, direction.get_movement_vec());
+    s.iter()
+        .all(|expected| ray.next().is_some_and(|(_, val)| val == expected))
 }
 
-fn parse_input(input: &str) -> Result<(Vec<&str>, usize), Error> {
+fn parse_input(input: &str) -> Result<RefBoard2d<'_, u8>, Error> {
     let mut lines = input.lines();
     let mut board = Vec::new();
     if let Some(first_line) = lines.next() {
-        board.push(first_line);
+        board.push(Cow::Borrowed(first_line.as_bytes()));
         let first_line_len = first_line.len();
         lines
             .try_fold(board, |mut vec, next_line| {
                 if next_line.len() != first_line_len {
                     Err(Error::ParseError("Not all line have same length!".into()))
                 } else {
-                    vec.push(next_line);
+                    vec.push(Cow::Borrowed(next_line.as_bytes()));
                     Ok(vec)
                 }
             })
-            .map(|concat_line| (concat_line, first_line_len))
+            .map(RefBoard2d::from)
     } else {
-        Ok((board, 0))
+        Ok(board.into())
     }
 }
 
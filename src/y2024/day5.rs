@@ -1,13 +1,13 @@
 use crate::error::Error;
+use crate::graph::adjacency::Graph;
 use crate::part_solver;
 use crate::utils::ures;
 use bit_set::BitSet;
-use std::collections::HashMap;
 use std::str::FromStr;
 
 part_solver!();
 
-type ChildrenRelationShip = HashMap<usize, BitSet<usize>>;
+type ChildrenRelationShip = Graph<usize, ()>;
 
 pub fn part1(input: &str) -> Result<ures, Error> {
     let (relationship, all_lists) = parse_input(input)?;
@@ -21,57 +21,37 @@ pub fn part1(input: &str) -> Result<ures, Error> {
 }
 
 pub fn part2(input: &str) -> Result<ures, Error> {
-    let (relationship, all_lists) = parse_input(input)?;
-    let res = all_lists
+    let (mut relationship, all_lists) = parse_input(input)?;
+    all_lists.iter().flatten().for_each(|val| {
+        relationship.intern(*val);
+    });
+    all_lists
         .iter()
         .filter(|list| !is_valid(list, &relationship))
-        .map(|list| (BitSet::<usize>::from_iter(list.iter().copied()), list))
-        .map(|(original_list_bit_set, list)| {
-            let mut fixed: Vec<usize> = Vec::with_capacity(list.len());
-            let mut visited = BitSet::<usize>::default();
-            list.iter().for_each(|val| {
-                fix_reverse(
-                    *val,
-                    &original_list_bit_set,
-                    &relationship,
-                    &mut visited,
-                    &mut fixed,
-                )
-            });
-            fixed
+        .map(|list| {
+            let nodes = list
+                .iter()
+                .filter_map(|val| relationship.node_id(val))
+                .collect::<BitSet>();
+            let fixed = relationship.topological_sort_within(&nodes)?;
+            let middle = fixed[fixed.len() / 2];
+            Ok(*relationship.node(middle).unwrap() as ures)
         })
-        .map(|list| list[list.len() / 2] as ures)
-        .sum();
-    Ok(res)
-}
-
-fn fix_reverse(
-    val: usize,
-    original_list_bit_set: &BitSet<usize>,
-    relationship: &ChildrenRelationShip,
-    visited: &mut BitSet<usize>,
-    res: &mut Vec<usize>,
-) {
-    if !visited.contains(val) {
-        visited.insert(val);
-        let mut children = relationship.get(&val).cloned().unwrap_or_default();
-        children.intersect_with(original_list_bit_set);
-        for child in children.iter() {
-            fix_reverse(child, original_list_bit_set, relationship, visited, res);
-        }
-        res.push(val);
-    }
+        .sum()
 }
 
 fn is_valid(list: &Vec<usize>, relationship: &ChildrenRelationShip) -> bool {
     let mut parents = BitSet::<usize>::default();
     for child in list {
-        if let Some(children) = relationship.get(child) {
-            if !children.is_disjoint(&parents) {
+        if let Some(child) = relationship.node_id(child) {
+            if relationship
+                .neighbors(child)
+                .any(|grand_child| parents.contains(grand_child))
+            {
                 return false;
             }
+            parents.insert(child);
         }
-        parents.insert(*child);
     }
 
     true
@@ -86,11 +66,11 @@ fn parse_input(input: &str) -> Result<(ChildrenRelationShip, Vec<Vec<usize>>), E
 
 fn parse_relationship(input: &str) -> Result<ChildrenRelationShip, Error> {
     input.lines().map(parse_relationship_line).try_fold(
-        HashMap::<usize, BitSet<usize>>::new(),
-        |mut map, parent_child| {
+        Graph::directed(),
+        |mut graph, parent_child| {
             let (parent, child) = parent_child?;
-            map.entry(parent).or_default().insert(child);
-            Ok(map)
+            graph.add_edge(parent, child, ());
+            Ok(graph)
         },
     )
 }
@@ -1,20 +1,12 @@
 use crate::error::Error;
 use crate::part_solver;
-use crate::space::space2d::Board2d;
-use crate::space::{Pos, Space};
+use crate::space::space2d::{Board2d, Direction};
+use crate::space::{Pos, Space, SpaceMut};
 use crate::utils::ures;
 use std::cmp::PartialEq;
 
 part_solver!();
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum Mask {
     Empty = 0,
@@ -33,41 +25,24 @@ enum Status {
     Done,
 }
 
-impl Direction {
-    #[inline]
-    const fn rotate(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-
-    #[inline]
-    fn rotate_self(&mut self) {
-        *self = self.rotate();
-    }
-
-    #[inline]
-    const fn mask(&self) -> Mask {
-        match self {
-            Direction::Up => Mask::Up,
-            Direction::Right => Mask::Right,
-            Direction::Down => Mask::Down,
-            Direction::Left => Mask::Left,
-        }
+#[inline]
+const fn direction_mask(direction: &Direction) -> Mask {
+    match direction {
+        Direction::North => Mask::Up,
+        Direction::East => Mask::Right,
+        Direction::South => Mask::Down,
+        Direction::West => Mask::Left,
     }
+}
 
-    #[inline]
-    const fn from_mask_u8(val: u8) -> Result<Direction, u8> {
-        match val {
-            1 => Ok(Direction::Up),
-            2 => Ok(Direction::Right),
-            4 => Ok(Direction::Down),
-            8 => Ok(Direction::Left),
-            _ => Err(val),
-        }
+#[inline]
+const fn direction_from_mask_u8(val: u8) -> Result<Direction, u8> {
+    match val {
+        1 => Ok(Direction::North),
+        2 => Ok(Direction::East),
+        4 => Ok(Direction::South),
+        8 => Ok(Direction::West),
+        _ => Err(val),
     }
 }
 
@@ -80,27 +55,24 @@ struct Guard {
 impl Guard {
     fn step(&mut self, board: &mut Board2d<u8>) -> Result<Status, Error> {
         let orig = self.coord;
-        let pos_mask = match self.facing {
-            Direction::Up => self.coord.shift(&[0, -1]),
-            Direction::Right => self.coord.shift(&[1, 0]),
-            Direction::Down => self.coord.shift(&[0, 1]),
-            Direction::Left => self.coord.shift(&[-1, 0]),
-        }
-        .inspect(|coord| self.coord = *coord)
-        .and_then(|next_coord| board.get(&next_coord));
+        let pos_mask = self
+            .coord
+            .shift(self.facing.get_movement_vec())
+            .inspect(|coord| self.coord = *coord)
+            .and_then(|next_coord| board.get(&next_coord));
 
         match pos_mask {
             None => Ok(Status::Done),
             Some(&pos_mask) => {
                 if pos_mask == Mask::Wall as u8 {
                     self.coord = orig;
-                    self.facing.rotate_self();
+                    self.facing = self.facing.clockwise_90();
                 }
 
                 let val = board.get_mut(&self.coord).ok_or_else(|| {
                     Error::InvalidState("guard position got outside the board".into())
                 })?;
-                let mask = self.facing.mask() as u8;
+                let mask = direction_mask(&self.facing) as u8;
                 if (*val & mask) != 0 {
                     Ok(Status::Cycle)
                 } else {
@@ -206,7 +178,7 @@ fn parse_input(input: &str) -> Result<(Board2d<u8>, Guard), Error> {
     let coord: [usize; 2] = start
         .ok_or_else(|| Error::ParseError("starting position not found".into()))?
         .into();
-    let direction = Direction::from_mask_u8(
+    let direction = direction_from_mask_u8(
         *board
             .get(&coord)
             .ok_or_else(|| Error::InvalidState("cannot get value at start position".into()))?,
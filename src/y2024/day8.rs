@@ -1,10 +1,10 @@
 use crate::error::Error;
+use crate::hash::{FastMap, FastSet};
 use crate::part_solver;
 use crate::space::space2d::RefBoard2d;
 use crate::space::{IterSpace, Space};
 use crate::utils::{ires, ures};
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
 part_solver!();
@@ -12,7 +12,10 @@ part_solver!();
 pub fn part1(input: &str) -> Result<ures, Error> {
     let board = parse_input(input);
     let (_, antinode_set) = board.iter().fold(
-        (HashMap::<u8, Vec<[usize; 2]>>::new(), HashSet::new()),
+        (
+            FastMap::<u8, Vec<[usize; 2]>>::default(),
+            FastSet::default(),
+        ),
         |(mut map, mut antinode_set), (coord, &value)| {
             if value != b'.' {
                 let prev_coords = map.entry(value).or_default();
@@ -34,7 +37,10 @@ pub fn part1(input: &str) -> Result<ures, Error> {
 pub fn part2(input: &str) -> Result<ures, Error> {
     let board = parse_input(input);
     let (_, antinode_set) = board.iter().fold(
-        (HashMap::<u8, Vec<[usize; 2]>>::new(), HashSet::new()),
+        (
+            FastMap::<u8, Vec<[usize; 2]>>::default(),
+            FastSet::default(),
+        ),
         |(mut map, mut antinode_set), (coord, &value)| {
             if value != b'.' {
                 let prev_coords = map.entry(value).or_default();